@@ -9,9 +9,22 @@ func _enter_tree():
 	add_custom_type("Rapier2DDynamicBody", "Node2D", preload("res://addons/rapier-2d-simple/classes/rapier_2d_dynamic_body.gdns"), null)
 	add_custom_type("Rapier2DKinematicPositionBasedBody", "Node2D", preload("res://addons/rapier-2d-simple/classes/rapier_2d_kinematic_position_based_body.gdns"), null)
 	add_custom_type("Rapier2DKinematicVelocityBasedBody", "Node2D", preload("res://addons/rapier-2d-simple/classes/rapier_2d_kinematic_velocity_based_body.gdns"), null)
+	add_custom_type("Rapier2DCharacterBody", "Node2D", preload("res://addons/rapier-2d-simple/classes/rapier_2d_character_body.gdns"), null)
 
 	add_custom_type("Rapier2DBallCollider", "Node2D", preload("res://addons/rapier-2d-simple/classes/rapier_2d_ball_collider.gdns"), null)
 	add_custom_type("Rapier2DCuboidCollider", "Node2D", preload("res://addons/rapier-2d-simple/classes/rapier_2d_cuboid_collider.gdns"), null)
+	add_custom_type("Rapier2DCapsuleCollider", "Node2D", preload("res://addons/rapier-2d-simple/classes/rapier_2d_capsule_collider.gdns"), null)
+	add_custom_type("Rapier2DSegmentCollider", "Node2D", preload("res://addons/rapier-2d-simple/classes/rapier_2d_segment_collider.gdns"), null)
+	add_custom_type("Rapier2DTriangleCollider", "Node2D", preload("res://addons/rapier-2d-simple/classes/rapier_2d_triangle_collider.gdns"), null)
+	add_custom_type("Rapier2DPolygonCollider", "Node2D", preload("res://addons/rapier-2d-simple/classes/rapier_2d_polygon_collider.gdns"), null)
+	add_custom_type("Rapier2DHeightfieldCollider", "Node2D", preload("res://addons/rapier-2d-simple/classes/rapier_2d_heightfield_collider.gdns"), null)
+
+	add_custom_type("Rapier2DFixedJoint", "Node2D", preload("res://addons/rapier-2d-simple/classes/rapier_2d_fixed_joint.gdns"), null)
+	add_custom_type("Rapier2DRevoluteJoint", "Node2D", preload("res://addons/rapier-2d-simple/classes/rapier_2d_revolute_joint.gdns"), null)
+	add_custom_type("Rapier2DPrismaticJoint", "Node2D", preload("res://addons/rapier-2d-simple/classes/rapier_2d_prismatic_joint.gdns"), null)
+	add_custom_type("Rapier2DRope", "Node2D", preload("res://addons/rapier-2d-simple/classes/rapier_2d_rope.gdns"), null)
+	add_custom_type("Rapier2DRagdoll", "Node2D", preload("res://addons/rapier-2d-simple/classes/rapier_2d_ragdoll.gdns"), null)
+	add_custom_type("Rapier2DRayCast", "Node2D", preload("res://addons/rapier-2d-simple/classes/rapier_2d_ray_cast.gdns"), null)
 	
 
 
@@ -23,6 +36,19 @@ func _exit_tree():
 	remove_custom_type("Rapier2DDynamicBody")
 	remove_custom_type("Rapier2DKinematicPositionBasedBody")
 	remove_custom_type("Rapier2DKinematicVelocityBasedBody")
+	remove_custom_type("Rapier2DCharacterBody")
 
 	remove_custom_type("Rapier2DBallCollider")
 	remove_custom_type("Rapier2DCuboidCollider")
+	remove_custom_type("Rapier2DCapsuleCollider")
+	remove_custom_type("Rapier2DSegmentCollider")
+	remove_custom_type("Rapier2DTriangleCollider")
+	remove_custom_type("Rapier2DPolygonCollider")
+	remove_custom_type("Rapier2DHeightfieldCollider")
+
+	remove_custom_type("Rapier2DFixedJoint")
+	remove_custom_type("Rapier2DRevoluteJoint")
+	remove_custom_type("Rapier2DPrismaticJoint")
+	remove_custom_type("Rapier2DRope")
+	remove_custom_type("Rapier2DRagdoll")
+	remove_custom_type("Rapier2DRayCast")
@@ -1,4 +1,8 @@
 pub mod common;
 pub mod godot_rapier_2d_body;
 pub mod godot_rapier_2d_collider;
+pub mod godot_rapier_2d_joint;
+pub mod godot_rapier_2d_ragdoll;
+pub mod godot_rapier_2d_ray_cast;
+pub mod godot_rapier_2d_rope;
 pub mod godot_rapier_2d_world;
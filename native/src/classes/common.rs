@@ -1,5 +1,11 @@
+use gdnative::prelude::*;
 use rapier2d::prelude::{self as r2d};
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+    sync::Mutex,
+};
 
 pub type World2DRef = Rc<RefCell<World2DState>>;
 
@@ -28,80 +34,262 @@ pub struct World2DState {
     pub impulse_joints: r2d::ImpulseJointSet,
     pub multibody_joints: r2d::MultibodyJointSet,
     pub ccd_solver: r2d::CCDSolver,
+    pub query_pipeline: r2d::QueryPipeline,
     pub hooks: (),
-    pub events: RawRefCollisionEventHandler,
+    pub events: CollisionEventQueue,
+    pub nodes: NodeRegistry,
+    // collider pairs touching as of the last step, keyed as reported when they started touching.
+    pub touching_colliders: HashMap<(r2d::ColliderHandle, r2d::ColliderHandle), TouchingColliders>,
+    pub joint_break_limits: HashMap<r2d::ImpulseJointHandle, JointBreakLimits>,
+    // colliders currently intersecting each sensor.
+    pub sensor_overlaps: HashMap<r2d::ColliderHandle, HashSet<r2d::ColliderHandle>>,
+    // sensors toggled, filtered anew or moved outside of a step, whose overlaps are rebuilt after the next one.
+    pub stale_sensors: HashSet<r2d::ColliderHandle>,
+    // body links raised by each touching collider pair.
+    pub collider_links: HashMap<(r2d::ColliderHandle, r2d::ColliderHandle), Vec<BodyLink>>,
+    // number of touching collider pairs holding each body link.
+    pub body_links: HashMap<BodyLink, usize>,
 }
 
+// nodes of a touching collider pair by instance id, so that ending it is signalled even once either is gone.
+#[derive(Clone, Copy)]
+pub struct TouchingColliders {
+    collider_1: r2d::ColliderHandle,
+    node_1: i64,
+    sensor_1: bool,
+    collider_2: r2d::ColliderHandle,
+    node_2: i64,
+    sensor_2: bool,
+}
+
+// a body touching another through one or more of its colliders, by node instance id.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BodyLink {
+    emitter: i64,
+    other: i64,
+    // whether the emitter's colliders overlap the other body as sensors.
+    sensor: bool,
+}
+
+impl BodyLink {
+    // the other body is passed as null if it has been freed in the meantime.
+    fn signal(&self, signal: &'static str) -> Option<PendingSignal> {
+        let emitter = node_from_instance_id(self.emitter)?;
+        let other = node_from_instance_id(self.other);
+        Some(PendingSignal::new(
+            emitter,
+            signal,
+            vec![other.to_variant()],
+        ))
+    }
+}
+
+// joint nodes are inserted either as impulse joints or as links of a multibody.
+#[derive(Clone, Copy)]
+pub enum JointHandle {
+    Impulse(r2d::ImpulseJointHandle),
+    Multibody(r2d::MultibodyJointHandle),
+}
+
+// solver load above which an impulse joint breaks apart, force and torque in pixel units.
+// the load along an axis sums the impulses locking it, and those of its limit and motor.
+#[derive(Clone, Copy)]
+pub struct JointBreakLimits {
+    pub force: f32,
+    pub torque: f32,
+}
+impl Default for JointBreakLimits {
+    fn default() -> Self {
+        Self {
+            force: f32::INFINITY,
+            torque: f32::INFINITY,
+        }
+    }
+}
+
+// maps rapier handles onto the instance ids of the godot nodes owning them.
 #[derive(Default)]
-pub struct RawRefCollisionEventHandler {}
-impl r2d::EventHandler for RawRefCollisionEventHandler {
-    fn handle_collision_event(
-        &self,
-        _bodies: &r2d::RigidBodySet,
-        colliders: &r2d::ColliderSet,
-        event: r2d::CollisionEvent,
-        _contact_pair: Option<&r2d::ContactPair>,
-    ) {
-        println!("event!");
-        use gdnative::prelude::*;
+pub struct NodeRegistry {
+    colliders: HashMap<r2d::ColliderHandle, i64>,
+    // reverse of `colliders`, for resolving nodes without scanning.
+    collider_handles: HashMap<i64, r2d::ColliderHandle>,
+    bodies: HashMap<r2d::RigidBodyHandle, i64>,
+    // reverse of `bodies`, for resolving nodes without scanning.
+    body_handles: HashMap<i64, r2d::RigidBodyHandle>,
+    joints: HashMap<r2d::ImpulseJointHandle, i64>,
+}
 
-        let collider_1 = &colliders[event.collider1()];
-        let collider_2 = &colliders[event.collider2()];
+impl NodeRegistry {
+    pub fn insert_collider(&mut self, handle: r2d::ColliderHandle, node: TRef<'_, Node2D>) {
+        self.colliders.insert(handle, node.get_instance_id());
+        self.collider_handles.insert(node.get_instance_id(), handle);
+    }
 
-        let collider_1_node_ref_ptr = collider_1.user_data as *const Ref<Node2D>;
-        let collider_2_node_ref_ptr = collider_2.user_data as *const Ref<Node2D>;
+    pub fn remove_collider(&mut self, handle: r2d::ColliderHandle) {
+        let Some(id) = self.colliders.remove(&handle) else {return};
+        // the node may have been registered again under a new handle since.
+        if self.collider_handles.get(&id) == Some(&handle) {
+            self.collider_handles.remove(&id);
+        }
+    }
 
-        let collider_1_node_ref = if collider_1_node_ref_ptr.is_null() {
-            panic!("collider 1 has no node2d user data assigned!")
-        } else {
-            unsafe { *collider_1_node_ref_ptr }
-        };
-        let collider_2_node_ref = if collider_2_node_ref_ptr.is_null() {
-            panic!("collider 2 has no node2d user data assigned!")
-        } else {
-            unsafe { *collider_2_node_ref_ptr }
-        };
+    pub fn insert_body(&mut self, handle: r2d::RigidBodyHandle, node: TRef<'_, Node2D>) {
+        self.bodies.insert(handle, node.get_instance_id());
+        self.body_handles.insert(node.get_instance_id(), handle);
+    }
 
-        let Some(collider_1_node) = (unsafe { collider_1_node_ref.assume_safe_if_sane() } ) else {return};
-        let Some(collider_2_node) = (unsafe { collider_2_node_ref.assume_safe_if_sane() } ) else {return};
+    pub fn remove_body(&mut self, handle: r2d::RigidBodyHandle) {
+        let Some(id) = self.bodies.remove(&handle) else {return};
+        // the node may have been registered again under a new handle since.
+        if self.body_handles.get(&id) == Some(&handle) {
+            self.body_handles.remove(&id);
+        }
+    }
 
-        if collider_1.is_sensor() {
-            if event.started() {
-                collider_1_node
-                    .emit_signal("collider_entered", &[collider_2_node_ref.to_variant()]);
-            } else {
-                collider_1_node.emit_signal("collider_exited", &[collider_2_node_ref.to_variant()]);
-            }
-        } else if collider_2.is_sensor() {
-            if event.started() {
-                collider_2_node
-                    .emit_signal("collider_entered", &[collider_1_node_ref.to_variant()]);
-            } else {
-                collider_2_node.emit_signal("collider_exited", &[collider_1_node_ref.to_variant()]);
-            }
-        } else if event.started() {
-            collider_1_node.emit_signal("collision_started", &[collider_2_node_ref.to_variant()]);
-            collider_2_node.emit_signal("collision_started", &[collider_1_node_ref.to_variant()]);
-        } else {
-            collider_1_node.emit_signal("collision_ended", &[collider_2_node_ref.to_variant()]);
-            collider_2_node.emit_signal("collision_ended", &[collider_1_node_ref.to_variant()]);
+    pub fn insert_joint(&mut self, handle: r2d::ImpulseJointHandle, node: TRef<'_, Node2D>) {
+        self.joints.insert(handle, node.get_instance_id());
+    }
+
+    pub fn remove_joint(&mut self, handle: r2d::ImpulseJointHandle) {
+        self.joints.remove(&handle);
+    }
+
+    pub fn collider_instance_id(&self, handle: r2d::ColliderHandle) -> Option<i64> {
+        self.colliders.get(&handle).copied()
+    }
+
+    // yields `None` if nothing is registered for the handle or the node has since been freed.
+    pub fn collider_node(&self, handle: r2d::ColliderHandle) -> Option<Ref<Node2D>> {
+        self.colliders
+            .get(&handle)
+            .and_then(|id| node_from_instance_id(*id))
+    }
+
+    // yields `None` if the node is not a body registered with this world.
+    pub fn body_handle(&self, node: TRef<'_, Node2D>) -> Option<r2d::RigidBodyHandle> {
+        self.body_handles.get(&node.get_instance_id()).copied()
+    }
+
+    // yields `None` if the node is not a collider registered with this world.
+    pub fn collider_handle(&self, node: TRef<'_, Node2D>) -> Option<r2d::ColliderHandle> {
+        self.collider_handles.get(&node.get_instance_id()).copied()
+    }
+
+    pub fn body_instance_id(&self, handle: r2d::RigidBodyHandle) -> Option<i64> {
+        self.bodies.get(&handle).copied()
+    }
+
+    // yields `None` if nothing is registered for the handle or the node has since been freed.
+    pub fn body_node(&self, handle: r2d::RigidBodyHandle) -> Option<Ref<Node2D>> {
+        self.bodies
+            .get(&handle)
+            .and_then(|id| node_from_instance_id(*id))
+    }
+
+    // whether the collider, or the body it is attached to, belongs to one of the nodes of `ids`.
+    pub fn collider_owned_by(
+        &self,
+        handle: r2d::ColliderHandle,
+        parent: Option<r2d::RigidBodyHandle>,
+        ids: &HashSet<i64>,
+    ) -> bool {
+        self.colliders
+            .get(&handle)
+            .is_some_and(|id| ids.contains(id))
+            || parent
+                .and_then(|b| self.bodies.get(&b))
+                .is_some_and(|id| ids.contains(id))
+    }
+
+    // yields `None` if nothing is registered for the handle or the node has since been freed.
+    pub fn joint_node(&self, handle: r2d::ImpulseJointHandle) -> Option<Ref<Node2D>> {
+        self.joints
+            .get(&handle)
+            .and_then(|id| node_from_instance_id(*id))
+    }
+}
+
+pub fn node_from_instance_id(id: i64) -> Option<Ref<Node2D>> {
+    // the physics world is only ever touched from the main thread.
+    unsafe { TRef::<Object>::try_from_instance_id(id) }
+        .and_then(|o| o.cast::<Node2D>())
+        .map(TRef::claim)
+}
+
+// godot signal resolved from a physics event, emitted once the world is no longer borrowed.
+pub struct PendingSignal {
+    emitter: Ref<Node2D>,
+    signal: &'static str,
+    args: Vec<Variant>,
+}
+
+impl PendingSignal {
+    fn new(emitter: Ref<Node2D>, signal: &'static str, args: Vec<Variant>) -> Self {
+        Self {
+            emitter,
+            signal,
+            args,
         }
     }
 
+    pub fn emit(self) {
+        // earlier signal handlers may have freed the emitter.
+        let Some(emitter) = (unsafe { self.emitter.assume_safe_if_sane() }) else {return};
+        emitter.emit_signal(self.signal, &self.args);
+    }
+}
+
+// collects events raised during the physics step, so that no signal is emitted while the world is borrowed.
+#[derive(Default)]
+pub struct CollisionEventQueue {
+    collision_events: Mutex<Vec<r2d::CollisionEvent>>,
+    contact_force_events: Mutex<Vec<r2d::ContactForceEvent>>,
+}
+
+impl CollisionEventQueue {
+    fn drain_collision_events(&mut self) -> Vec<r2d::CollisionEvent> {
+        std::mem::take(self.collision_events.get_mut().unwrap())
+    }
+
+    fn drain_contact_force_events(&mut self) -> Vec<r2d::ContactForceEvent> {
+        std::mem::take(self.contact_force_events.get_mut().unwrap())
+    }
+}
+
+impl r2d::EventHandler for CollisionEventQueue {
+    fn handle_collision_event(
+        &self,
+        _bodies: &r2d::RigidBodySet,
+        _colliders: &r2d::ColliderSet,
+        event: r2d::CollisionEvent,
+        _contact_pair: Option<&r2d::ContactPair>,
+    ) {
+        self.collision_events.lock().unwrap().push(event);
+    }
+
     fn handle_contact_force_event(
         &self,
-        _dt: r2d::Real,
+        dt: r2d::Real,
         _bodies: &r2d::RigidBodySet,
         _colliders: &r2d::ColliderSet,
-        _contact_pair: &r2d::ContactPair,
-        _total_force_magnitude: r2d::Real,
+        contact_pair: &r2d::ContactPair,
+        total_force_magnitude: r2d::Real,
     ) {
-        // intentionally left empty.
+        self.contact_force_events
+            .lock()
+            .unwrap()
+            .push(r2d::ContactForceEvent::from_contact_pair(
+                dt,
+                contact_pair,
+                total_force_magnitude,
+            ));
     }
 }
 
 impl World2DState {
-    pub fn tick(&mut self) {
+    // steps the simulation, returning the signals to emit once the world has been released.
+    #[must_use]
+    pub fn tick(&mut self, ppm: PixelsPerMeter) -> Vec<PendingSignal> {
         self.physics_pipeline.step(
             &self.gravity,
             &self.integration_parameters,
@@ -115,7 +303,407 @@ impl World2DState {
             &mut self.ccd_solver,
             &self.hooks,
             &self.events,
-        )
+        );
+        // scene queries see the poses of the last step.
+        self.query_pipeline.update(
+            &self.island_manager,
+            &self.rigid_body_set,
+            &self.collider_set,
+        );
+
+        let collision_events = self.events.drain_collision_events();
+        let contact_force_events = self.events.drain_contact_force_events();
+
+        collision_events
+            .iter()
+            .for_each(|e| self.track_sensor_overlap(e));
+        std::mem::take(&mut self.stale_sensors)
+            .into_iter()
+            .for_each(|c| self.refresh_sensor_overlaps(c));
+        let body_signals: Vec<_> = collision_events
+            .iter()
+            .flat_map(|e| self.body_event_signals(e))
+            .collect();
+        let collision_signals: Vec<_> = collision_events
+            .into_iter()
+            .flat_map(|e| self.collision_event_signals(e, ppm))
+            .collect();
+        let broken_joint_signals = self.break_joints(ppm);
+
+        collision_signals
+            .into_iter()
+            .chain(
+                contact_force_events
+                    .into_iter()
+                    .flat_map(|e| self.contact_force_event_signals(e, ppm)),
+            )
+            .chain(body_signals)
+            .chain(broken_joint_signals)
+            .collect()
+    }
+
+    // removes joints whose solver impulses of the last step exceed their break limits.
+    fn break_joints(&mut self, ppm: PixelsPerMeter) -> Vec<PendingSignal> {
+        let dt = self.integration_parameters.dt;
+        let broken: Vec<_> = self
+            .joint_break_limits
+            .iter()
+            .filter_map(|(handle, limits)| {
+                let joint = self.impulse_joints.get(*handle)?;
+                let axis_impulse = |axis: r2d::JointAxis| {
+                    let i = axis as usize;
+                    joint.impulses[i] + joint.data.limits[i].impulse + joint.data.motors[i].impulse
+                };
+                let impulse =
+                    axis_impulse(r2d::JointAxis::X).hypot(axis_impulse(r2d::JointAxis::Y)) * ppm.0;
+                // torques scale with the square of the length.
+                let torque = axis_impulse(r2d::JointAxis::AngX).abs() / dt * ppm.0 * ppm.0;
+                (impulse / dt > limits.force || torque > limits.torque)
+                    .then_some((*handle, impulse))
+            })
+            .collect();
+
+        broken
+            .into_iter()
+            .filter_map(|(handle, impulse)| {
+                let node = self.nodes.joint_node(handle);
+                self.remove_impulse_joint(handle);
+                node.map(|n| PendingSignal::new(n, "joint_broken", vec![impulse.to_variant()]))
+            })
+            .collect()
+    }
+
+    // keeps the overlap sets of sensors in step with their intersection events.
+    fn track_sensor_overlap(&mut self, event: &r2d::CollisionEvent) {
+        if !event.sensor() {
+            return;
+        }
+        let (c1, c2) = (event.collider1(), event.collider2());
+        for (sensor, other) in [(c1, c2), (c2, c1)] {
+            if event.started() {
+                let Some(collider) = self.collider_set.get(sensor) else {continue};
+                if collider.is_sensor() {
+                    self.sensor_overlaps
+                        .entry(sensor)
+                        .or_default()
+                        .insert(other);
+                }
+            } else if let Some(overlaps) = self.sensor_overlaps.get_mut(&sensor) {
+                overlaps.remove(&other);
+            }
+        }
+    }
+
+    // the narrow phase only catches up with the collider on the next step.
+    pub fn mark_sensor_stale(&mut self, handle: r2d::ColliderHandle) {
+        self.stale_sensors.insert(handle);
+    }
+
+    // marks the sensors attached to a body that is moved outside of a step.
+    pub fn mark_attached_sensors_stale(&mut self, handle: r2d::RigidBodyHandle) {
+        let Some(body) = self.rigid_body_set.get(handle) else {return};
+        let collider_set = &self.collider_set;
+        self.stale_sensors.extend(
+            body.colliders()
+                .iter()
+                .filter(|c| collider_set.get(**c).is_some_and(r2d::Collider::is_sensor)),
+        );
+    }
+
+    // rebuilds the overlap set of a collider from the narrow phase, once it has caught up with the collider.
+    fn refresh_sensor_overlaps(&mut self, handle: r2d::ColliderHandle) {
+        if !self
+            .collider_set
+            .get(handle)
+            .is_some_and(r2d::Collider::is_sensor)
+        {
+            self.sensor_overlaps.remove(&handle);
+            return;
+        }
+        let overlaps = self
+            .narrow_phase
+            .intersections_with(handle)
+            .filter(|(_, _, intersecting)| *intersecting)
+            .map(|(c1, c2, _)| if c1 == handle { c2 } else { c1 })
+            .collect();
+        self.sensor_overlaps.insert(handle, overlaps);
+    }
+
+    // colliders intersecting the sensor as of the last step, none if it is no sensor.
+    pub fn overlapping_colliders(
+        &self,
+        handle: r2d::ColliderHandle,
+    ) -> impl Iterator<Item = r2d::ColliderHandle> + '_ {
+        self.sensor_overlaps
+            .get(&handle)
+            .into_iter()
+            .flatten()
+            .copied()
+    }
+
+    // reference counts collider pairs per body, signalling when the first one starts and the last one stops touching.
+    fn body_event_signals(&mut self, event: &r2d::CollisionEvent) -> Vec<PendingSignal> {
+        let (c1, c2) = (event.collider1(), event.collider2());
+        if event.stopped() {
+            let links = self
+                .collider_links
+                .remove(&(c1, c2))
+                .or_else(|| self.collider_links.remove(&(c2, c1)))
+                .unwrap_or_default();
+            return links
+                .into_iter()
+                .filter_map(|link| {
+                    let count = self.body_links.get_mut(&link)?;
+                    *count -= 1;
+                    if *count > 0 {
+                        return None;
+                    }
+                    self.body_links.remove(&link);
+                    link.signal(if link.sensor {
+                        "body_exited"
+                    } else {
+                        "body_collision_ended"
+                    })
+                })
+                .collect();
+        }
+
+        let links = self.body_links_between(c1, c2, event.sensor());
+        let signals = links
+            .iter()
+            .filter_map(|link| {
+                let count = self.body_links.entry(*link).or_default();
+                *count += 1;
+                if *count > 1 {
+                    return None;
+                }
+                link.signal(if link.sensor {
+                    "body_entered"
+                } else {
+                    "body_collision_started"
+                })
+            })
+            .collect();
+        self.collider_links.insert((c1, c2), links);
+        signals
+    }
+
+    // both bodies get to know of a collision, only the sensor side of an overlap.
+    fn body_links_between(
+        &self,
+        collider_1: r2d::ColliderHandle,
+        collider_2: r2d::ColliderHandle,
+        sensor: bool,
+    ) -> Vec<BodyLink> {
+        let side = |handle: r2d::ColliderHandle| {
+            let collider = self.collider_set.get(handle)?;
+            let body = self.nodes.body_instance_id(collider.parent()?)?;
+            Some((body, collider.is_sensor()))
+        };
+        let (Some((body_1, sensor_1)), Some((body_2, sensor_2))) = (side(collider_1), side(collider_2)) else {return vec![]};
+        if body_1 == body_2 {
+            return vec![];
+        }
+        [(body_1, sensor_1, body_2), (body_2, sensor_2, body_1)]
+            .into_iter()
+            .filter(|(_, is_sensor, _)| !sensor || *is_sensor)
+            .map(|(emitter, _, other)| BodyLink {
+                emitter,
+                other,
+                sensor,
+            })
+            .collect()
+    }
+
+    fn collision_event_signals(
+        &mut self,
+        event: r2d::CollisionEvent,
+        ppm: PixelsPerMeter,
+    ) -> Vec<PendingSignal> {
+        let (c1, c2) = (event.collider1(), event.collider2());
+        let pair = if event.started() {
+            let Some(pair) = self.touching_pair(c1, c2) else {return vec![]};
+            self.touching_colliders.insert((c1, c2), pair);
+            pair
+        } else {
+            // colliders removed from the world are gone from the set and the registry by now.
+            let pair = self
+                .touching_colliders
+                .remove(&(c1, c2))
+                .or_else(|| self.touching_colliders.remove(&(c2, c1)));
+            let Some(pair) = pair else {return vec![]};
+            pair
+        };
+
+        // a node freed in the meantime is passed as null.
+        let node_1 = node_from_instance_id(pair.node_1);
+        let node_2 = node_from_instance_id(pair.node_2);
+        if pair.sensor_1 || pair.sensor_2 {
+            let signal = if event.started() {
+                "collider_entered"
+            } else {
+                "collider_exited"
+            };
+            let (emitter, other) = if pair.sensor_1 {
+                (node_1, node_2)
+            } else {
+                (node_2, node_1)
+            };
+            emitter
+                .map(|e| PendingSignal::new(e, signal, vec![other.to_variant()]))
+                .into_iter()
+                .collect()
+        } else {
+            let signal = if event.started() {
+                "collision_started"
+            } else {
+                "collision_ended"
+            };
+            let (c1, c2) = (pair.collider_1, pair.collider_2);
+            [(node_1, node_2, c1, c2), (node_2, node_1, c2, c1)]
+                .into_iter()
+                .filter_map(|(emitter, other, emitter_handle, other_handle)| {
+                    Some(PendingSignal::new(
+                        emitter?,
+                        signal,
+                        vec![
+                            other.to_variant(),
+                            self.collision_info(emitter_handle, other_handle, ppm)
+                                .to_variant(),
+                        ],
+                    ))
+                })
+                .collect()
+        }
+    }
+
+    // registered nodes of two colliders starting to touch.
+    fn touching_pair(
+        &self,
+        collider_1: r2d::ColliderHandle,
+        collider_2: r2d::ColliderHandle,
+    ) -> Option<TouchingColliders> {
+        Some(TouchingColliders {
+            collider_1,
+            node_1: self.nodes.collider_instance_id(collider_1)?,
+            sensor_1: self.collider_set.get(collider_1)?.is_sensor(),
+            collider_2,
+            node_2: self.nodes.collider_instance_id(collider_2)?,
+            sensor_2: self.collider_set.get(collider_2)?.is_sensor(),
+        })
+    }
+
+    // contact details between two colliders as seen from `emitter`, in pixel units.
+    fn collision_info(
+        &self,
+        emitter: r2d::ColliderHandle,
+        other: r2d::ColliderHandle,
+        ppm: PixelsPerMeter,
+    ) -> Dictionary {
+        let mut points = vec![];
+        let mut normal = r2d::Vector::zeros();
+        let mut depth: r2d::Real = 0.;
+        let mut impulse: r2d::Real = 0.;
+
+        if let Some(pair) = self.narrow_phase.contact_pair(emitter, other) {
+            if let Some(collider_1) = self.collider_set.get(pair.collider1) {
+                // manifold normals point away from the pair's first collider.
+                let sign = if pair.collider1 == emitter { 1. } else { -1. };
+                for manifold in &pair.manifolds {
+                    normal += manifold.data.normal * sign;
+                    // contacts of composite shapes are local to the subshape involved.
+                    let shape_position = manifold
+                        .subshape_pos1
+                        .map_or(*collider_1.position(), |p| collider_1.position() * p);
+                    for pt in manifold.contacts() {
+                        points.push(shape_position * pt.local_p1);
+                        depth = depth.max(-pt.dist);
+                        impulse += pt.data.impulse;
+                    }
+                }
+            }
+        }
+        let normal = normal.try_normalize(r2d::Real::EPSILON).unwrap_or_default();
+
+        let velocity_at = |handle: r2d::ColliderHandle, point: &r2d::Point<r2d::Real>| {
+            self.collider_set
+                .get(handle)
+                .and_then(r2d::Collider::parent)
+                .and_then(|b| self.rigid_body_set.get(b))
+                .map(|b| b.velocity_at_point(point))
+                .unwrap_or_default()
+        };
+        let centroid = points.iter().fold(r2d::Point::origin(), |acc, p| {
+            acc + p.coords / points.len() as r2d::Real
+        });
+        let relative_velocity = velocity_at(other, &centroid) - velocity_at(emitter, &centroid);
+
+        let info = Dictionary::new();
+        info.insert(
+            "points",
+            Vector2Array::from_vec(
+                points
+                    .iter()
+                    .map(|p| Vector2 {
+                        x: p.x * ppm.0,
+                        y: p.y * ppm.0,
+                    })
+                    .collect(),
+            ),
+        );
+        info.insert(
+            "normal",
+            Vector2 {
+                x: normal.x,
+                y: normal.y,
+            },
+        );
+        info.insert("depth", depth * ppm.0);
+        info.insert(
+            "relative_velocity",
+            Vector2 {
+                x: relative_velocity.x * ppm.0,
+                y: relative_velocity.y * ppm.0,
+            },
+        );
+        info.insert("impulse", impulse * ppm.0);
+        info.into_shared()
+    }
+
+    fn contact_force_event_signals(
+        &self,
+        event: r2d::ContactForceEvent,
+        ppm: PixelsPerMeter,
+    ) -> Vec<PendingSignal> {
+        let Some(collider_1_node) = self.nodes.collider_node(event.collider1) else {return vec![]};
+        let Some(collider_2_node) = self.nodes.collider_node(event.collider2) else {return vec![]};
+
+        let magnitude = event.total_force_magnitude * ppm.0;
+        // the contact normal points from collider 1 towards collider 2, each is pushed away from the other.
+        let direction = Vector2 {
+            x: event.max_force_direction.x,
+            y: event.max_force_direction.y,
+        };
+        vec![
+            PendingSignal::new(
+                collider_1_node,
+                "contact_force",
+                vec![
+                    collider_2_node.to_variant(),
+                    magnitude.to_variant(),
+                    (-direction).to_variant(),
+                ],
+            ),
+            PendingSignal::new(
+                collider_2_node,
+                "contact_force",
+                vec![
+                    collider_1_node.to_variant(),
+                    magnitude.to_variant(),
+                    direction.to_variant(),
+                ],
+            ),
+        ]
     }
 
     pub fn insert_collider_with_parent(
@@ -127,7 +715,21 @@ impl World2DState {
             .insert_with_parent(coll, parent_handle, &mut self.rigid_body_set)
     }
 
+    // rapier removes the joints attached to a body along with it, so their entries go too.
+    fn forget_attached_joints(&mut self, body: r2d::RigidBodyHandle) {
+        let attached: Vec<_> = self
+            .impulse_joints
+            .attached_joints(body)
+            .map(|(_, _, handle, _)| handle)
+            .collect();
+        attached.into_iter().for_each(|handle| {
+            self.nodes.remove_joint(handle);
+            self.joint_break_limits.remove(&handle);
+        });
+    }
+
     pub fn remove_rigid_body(&mut self, handle: r2d::RigidBodyHandle) {
+        self.forget_attached_joints(handle);
         self.rigid_body_set.remove(
             handle,
             &mut self.island_manager,
@@ -138,7 +740,73 @@ impl World2DState {
         );
     }
 
+    // for bodies generated without collider nodes of their own, such as rope segments.
+    pub fn remove_rigid_body_and_colliders(&mut self, handle: r2d::RigidBodyHandle) {
+        self.forget_attached_joints(handle);
+        self.rigid_body_set.remove(
+            handle,
+            &mut self.island_manager,
+            &mut self.collider_set,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            true,
+        );
+    }
+
+    pub fn insert_impulse_joint(
+        &mut self,
+        body_1: r2d::RigidBodyHandle,
+        body_2: r2d::RigidBodyHandle,
+        joint: r2d::GenericJoint,
+        node: TRef<'_, Node2D>,
+        break_limits: JointBreakLimits,
+    ) -> r2d::ImpulseJointHandle {
+        let handle = self.impulse_joints.insert(body_1, body_2, joint, true);
+        self.nodes.insert_joint(handle, node);
+        self.joint_break_limits.insert(handle, break_limits);
+        handle
+    }
+
+    pub fn remove_impulse_joint(&mut self, handle: r2d::ImpulseJointHandle) {
+        self.impulse_joints.remove(handle, true);
+        self.nodes.remove_joint(handle);
+        self.joint_break_limits.remove(&handle);
+    }
+
+    pub fn remove_joint(&mut self, handle: JointHandle) {
+        match handle {
+            JointHandle::Impulse(handle) => self.remove_impulse_joint(handle),
+            JointHandle::Multibody(handle) => self.multibody_joints.remove(handle, true),
+        }
+    }
+
+    pub fn joint_data(&self, handle: JointHandle) -> Option<&r2d::GenericJoint> {
+        match handle {
+            JointHandle::Impulse(handle) => self.impulse_joints.get(handle).map(|j| &j.data),
+            JointHandle::Multibody(handle) => self
+                .multibody_joints
+                .get(handle)
+                .and_then(|(multibody, link)| multibody.link(link))
+                .map(|l| &l.joint.data),
+        }
+    }
+
+    pub fn joint_data_mut(&mut self, handle: JointHandle) -> Option<&mut r2d::GenericJoint> {
+        match handle {
+            JointHandle::Impulse(handle) => {
+                self.impulse_joints.get_mut(handle).map(|j| &mut j.data)
+            }
+            JointHandle::Multibody(handle) => self
+                .multibody_joints
+                .get_mut(handle)
+                .and_then(|(multibody, link)| multibody.link_mut(link))
+                .map(|l| &mut l.joint.data),
+        }
+    }
+
     pub fn remove_collider(&mut self, handle: r2d::ColliderHandle) {
+        self.sensor_overlaps.remove(&handle);
+        self.stale_sensors.remove(&handle);
         self.collider_set.remove(
             handle,
             &mut self.island_manager,
@@ -160,3 +828,16 @@ macro_rules! not_in_editor {
     };
 }
 pub(crate) use not_in_editor;
+
+macro_rules! should_draw {
+    ($base : ident) => {{
+        use gdnative::api::Engine;
+        Engine::godot_singleton().is_editor_hint()
+            || $base
+                .get_tree()
+                .and_then(|t| unsafe { t.assume_safe_if_sane() })
+                .map(|a| a.is_debugging_collisions_hint())
+                .unwrap_or(true)
+    }};
+}
+pub(crate) use should_draw;
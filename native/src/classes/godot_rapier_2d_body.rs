@@ -1,11 +1,19 @@
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
 use std::rc::Rc;
 use std::{cell::RefCell, rc::Weak};
 
+use gdnative::export::hint::{FloatHint, IntHint, RangeHint};
 use gdnative::prelude::*;
+use rapier2d::control::{CharacterAutostep, CharacterLength, KinematicCharacterController};
 use rapier2d::prelude as r2d;
 
 use super::common::{not_in_editor, PixelsPerMeter, World2DAware, World2DState};
-use super::godot_rapier_2d_collider::{GodotRapier2DColliderBall, GodotRapier2DColliderCuboid};
+use super::godot_rapier_2d_collider::{
+    GodotRapier2DColliderBall, GodotRapier2DColliderCapsule, GodotRapier2DColliderCuboid,
+    GodotRapier2DColliderHeightfield, GodotRapier2DColliderPolygon, GodotRapier2DColliderSegment,
+    GodotRapier2DColliderTriangle,
+};
+use super::godot_rapier_2d_ray_cast::GodotRapier2DRayCast;
 
 #[derive(Default)]
 struct WorldProps {
@@ -13,6 +21,22 @@ struct WorldProps {
     world_ppm: Option<PixelsPerMeter>,
     handle: Option<r2d::RigidBodyHandle>,
 }
+
+// receive rigid body handle or abort current routine.
+macro_rules! handle_or_return {
+    ($s:ident,$h:ident) => {
+        let Some($h) = $s.world_props.handle else {return};
+    };
+}
+
+// receive mutable world ref or abort current routine.
+macro_rules! mut_world_or_return {
+    ($s:ident,$i:ident) => {
+        let Some(world) = $s.world_props.world_ref.as_ref().and_then(Weak::upgrade) else {return};
+        let mut $i = world.borrow_mut();
+    };
+}
+
 macro_rules! proxy_world_awareness_to_world_props {
     ($t:ty) => {
         impl World2DAware<TRef<'_, Node2D>> for $t {
@@ -28,6 +52,7 @@ macro_rules! proxy_world_awareness_to_world_props {
                 self.world_props.world_ref = Some(Rc::downgrade(world_ref));
                 self.world_props.world_ppm = Some(ppm);
                 self.world_props.handle = Some(handle);
+                world_ref.borrow_mut().nodes.insert_body(handle, base);
 
                 base.get_children().iter().for_each(|c| {
                     let Ok(n) = c.try_to() else {return;};
@@ -47,7 +72,9 @@ macro_rules! proxy_world_awareness_to_world_props {
 
                 if let Some(world) = self.world_props.world_ref.as_ref().and_then(Weak::upgrade) {
                     if let Some(handle) = self.world_props.handle {
-                        world.borrow_mut().remove_rigid_body(handle)
+                        let mut world = world.borrow_mut();
+                        world.nodes.remove_body(handle);
+                        world.remove_rigid_body(handle)
                     }
                 };
 
@@ -59,8 +86,7 @@ macro_rules! proxy_world_awareness_to_world_props {
     };
 }
 
-// bridges common props towards collider builder.
-// stores a raw pointer box to reference of `base` on the heap, which must be dropped eventually.
+// bridges common props towards rigid body builder.
 macro_rules! complete_body {
     ($self:ident,$base:ident,$ppm:ident,$builder:expr) => {{
         use rapier2d::prelude::*;
@@ -70,47 +96,391 @@ macro_rules! complete_body {
                 $base.position().y / $ppm.0
             ])
             .rotation($base.rotation() as f32)
-            .user_data(&$base.claim() as *const Ref<Node2D> as u128)
             .build()
     }};
 }
 
+// body-level signals, emitted once per other body however many colliders touch it.
+macro_rules! register_emitted_signals {
+    ($builder:ident) => {
+        $builder
+            .signal("body_entered")
+            .with_param_untyped("body")
+            .done();
+        $builder
+            .signal("body_exited")
+            .with_param_untyped("body")
+            .done();
+        $builder
+            .signal("body_collision_started")
+            .with_param_untyped("body")
+            .done();
+        $builder
+            .signal("body_collision_ended")
+            .with_param_untyped("body")
+            .done();
+    };
+}
+
 #[derive(NativeClass)]
 #[inherit(Node2D)]
+#[register_with(Self::register_properties)]
 pub struct GodotRapier2DFixedBody {
     world_props: WorldProps,
 }
 proxy_world_awareness_to_world_props!(GodotRapier2DFixedBody);
+// properties of dynamic bodies.
+struct DynamicBodyProps {
+    linear_damping: f32,
+    angular_damping: f32,
+    gravity_scale: f32,
+    ccd_enabled: bool,
+    can_sleep: bool,
+    dominance_group: i64,
+    lock_rotation: bool,
+    lock_translation_x: bool,
+    lock_translation_y: bool,
+    // in pixels per second.
+    linear_velocity: Vector2,
+    // in radians per second.
+    angular_velocity: f32,
+}
+impl Default for DynamicBodyProps {
+    fn default() -> Self {
+        Self {
+            linear_damping: 0.,
+            angular_damping: 0.,
+            gravity_scale: 1.,
+            ccd_enabled: false,
+            can_sleep: true,
+            dominance_group: 0,
+            lock_rotation: false,
+            lock_translation_x: false,
+            lock_translation_y: false,
+            linear_velocity: Vector2::ZERO,
+            angular_velocity: 0.,
+        }
+    }
+}
+impl DynamicBodyProps {
+    fn dominance_group(&self) -> i8 {
+        self.dominance_group.clamp(-127, 127) as i8
+    }
+
+    fn locked_axes(&self) -> r2d::LockedAxes {
+        let mut axes = r2d::LockedAxes::empty();
+        axes.set(r2d::LockedAxes::ROTATION_LOCKED, self.lock_rotation);
+        axes.set(
+            r2d::LockedAxes::TRANSLATION_LOCKED_X,
+            self.lock_translation_x,
+        );
+        axes.set(
+            r2d::LockedAxes::TRANSLATION_LOCKED_Y,
+            self.lock_translation_y,
+        );
+        axes
+    }
+
+    fn linvel(&self, ppm: PixelsPerMeter) -> r2d::Vector<r2d::Real> {
+        use rapier2d::prelude::*;
+        vector![
+            self.linear_velocity.x / ppm.0,
+            self.linear_velocity.y / ppm.0
+        ]
+    }
+
+    // linear and angular sleep thresholds; negative ones keep the body awake.
+    fn sleep_thresholds(&self) -> (r2d::Real, r2d::Real) {
+        if self.can_sleep {
+            (
+                r2d::RigidBodyActivation::default_linear_threshold(),
+                r2d::RigidBodyActivation::default_angular_threshold(),
+            )
+        } else {
+            (-1., -1.)
+        }
+    }
+}
+
 #[derive(NativeClass)]
 #[inherit(Node2D)]
+#[register_with(Self::register_properties)]
 pub struct GodotRapier2DDynamicBody {
     world_props: WorldProps,
+    dynamic_props: DynamicBodyProps,
 }
 proxy_world_awareness_to_world_props!(GodotRapier2DDynamicBody);
 #[derive(NativeClass)]
 #[inherit(Node2D)]
+#[register_with(Self::register_properties)]
 pub struct GodotRapier2DKinematicPositionBasedBody {
     world_props: WorldProps,
 }
 proxy_world_awareness_to_world_props!(GodotRapier2DKinematicPositionBasedBody);
+// properties of kinematic velocity-based bodies.
+#[derive(Default)]
+struct KinematicVelocityProps {
+    // in pixels per second.
+    linear_velocity: Vector2,
+    // in radians per second.
+    angular_velocity: f32,
+}
+impl KinematicVelocityProps {
+    fn linvel(&self, ppm: PixelsPerMeter) -> r2d::Vector<r2d::Real> {
+        use rapier2d::prelude::*;
+        vector![
+            self.linear_velocity.x / ppm.0,
+            self.linear_velocity.y / ppm.0
+        ]
+    }
+}
+
 #[derive(NativeClass)]
 #[inherit(Node2D)]
+#[register_with(Self::register_properties)]
 pub struct GodotRapier2DKinematicVelocityBasedBody {
     world_props: WorldProps,
+    velocity_props: KinematicVelocityProps,
 }
 proxy_world_awareness_to_world_props!(GodotRapier2DKinematicVelocityBasedBody);
 
+// properties of character bodies, lengths in pixels.
+struct CharacterProps {
+    max_slope_climb_angle: f32,
+    min_slope_slide_angle: f32,
+    // 0 disables stepping onto obstacles.
+    autostep_max_height: f32,
+    autostep_min_width: f32,
+    // 0 disables snapping down onto the ground.
+    snap_to_ground: f32,
+}
+impl Default for CharacterProps {
+    fn default() -> Self {
+        Self {
+            max_slope_climb_angle: FRAC_PI_4,
+            min_slope_slide_angle: FRAC_PI_4,
+            autostep_max_height: 0.,
+            autostep_min_width: 0.,
+            snap_to_ground: 0.,
+        }
+    }
+}
+impl CharacterProps {
+    fn controller(&self, ppm: PixelsPerMeter) -> KinematicCharacterController {
+        KinematicCharacterController {
+            // godot's y axis points down.
+            up: -r2d::Vector::y_axis(),
+            max_slope_climb_angle: self.max_slope_climb_angle,
+            min_slope_slide_angle: self.min_slope_slide_angle,
+            autostep: (self.autostep_max_height > 0.).then_some(CharacterAutostep {
+                max_height: CharacterLength::Absolute(self.autostep_max_height / ppm.0),
+                min_width: CharacterLength::Absolute(self.autostep_min_width / ppm.0),
+                include_dynamic_bodies: true,
+            }),
+            snap_to_ground: (self.snap_to_ground > 0.)
+                .then_some(CharacterLength::Absolute(self.snap_to_ground / ppm.0)),
+            ..KinematicCharacterController::default()
+        }
+    }
+}
+
+// outcome of the last `move_and_slide`.
+#[derive(Default)]
+struct CharacterState {
+    on_floor: bool,
+    on_wall: bool,
+    collisions: Vec<Dictionary>,
+}
+
+// kinematic body moved by script through rapier's character controller.
+#[derive(NativeClass)]
+#[inherit(Node2D)]
+#[register_with(Self::register_properties)]
+pub struct GodotRapier2DCharacterBody {
+    world_props: WorldProps,
+    character_props: CharacterProps,
+    character_state: CharacterState,
+}
+proxy_world_awareness_to_world_props!(GodotRapier2DCharacterBody);
+
 impl GodotRapier2DFixedBody {
+    const FOLLOWS_NODE: bool = false;
+
     pub fn build_rigid_body(&self, base: TRef<'_, Node2D>, ppm: PixelsPerMeter) -> r2d::RigidBody {
         complete_body!(self, base, ppm, r2d::RigidBodyBuilder::fixed())
     }
+
+    fn register_properties(builder: &ClassBuilder<GodotRapier2DFixedBody>) {
+        register_emitted_signals!(builder);
+    }
 }
 impl GodotRapier2DDynamicBody {
+    const FOLLOWS_NODE: bool = false;
+
     pub fn build_rigid_body(&self, base: TRef<'_, Node2D>, ppm: PixelsPerMeter) -> r2d::RigidBody {
-        complete_body!(self, base, ppm, r2d::RigidBodyBuilder::dynamic())
+        let props = &self.dynamic_props;
+        complete_body!(
+            self,
+            base,
+            ppm,
+            r2d::RigidBodyBuilder::dynamic()
+                .linear_damping(props.linear_damping)
+                .angular_damping(props.angular_damping)
+                .gravity_scale(props.gravity_scale)
+                .ccd_enabled(props.ccd_enabled)
+                .can_sleep(props.can_sleep)
+                .dominance_group(props.dominance_group())
+                .locked_axes(props.locked_axes())
+                .linvel(props.linvel(ppm))
+                .angvel(props.angular_velocity)
+        )
+    }
+
+    // runs `f` on the simulated body, if there is one yet.
+    fn with_body<R>(&self, f: impl FnOnce(&mut r2d::RigidBody, PixelsPerMeter) -> R) -> Option<R> {
+        let handle = self.world_props.handle?;
+        let ppm = self.world_props.world_ppm?;
+        let world = self
+            .world_props
+            .world_ref
+            .as_ref()
+            .and_then(Weak::upgrade)?;
+        let mut world = world.borrow_mut();
+        world.rigid_body_set.get_mut(handle).map(|b| f(b, ppm))
+    }
+
+    fn register_properties(builder: &ClassBuilder<GodotRapier2DDynamicBody>) {
+        builder
+            .property("linear_damping")
+            .with_default(0.)
+            .with_getter(|s, _| s.dynamic_props.linear_damping)
+            .with_setter(|s, _, new_val| {
+                s.dynamic_props.linear_damping = new_val;
+                handle_or_return!(s, handle);
+                mut_world_or_return!(s, world);
+                world.rigid_body_set[handle].set_linear_damping(new_val);
+            })
+            .done();
+        builder
+            .property("angular_damping")
+            .with_default(0.)
+            .with_getter(|s, _| s.dynamic_props.angular_damping)
+            .with_setter(|s, _, new_val| {
+                s.dynamic_props.angular_damping = new_val;
+                handle_or_return!(s, handle);
+                mut_world_or_return!(s, world);
+                world.rigid_body_set[handle].set_angular_damping(new_val);
+            })
+            .done();
+        builder
+            .property("gravity_scale")
+            .with_default(1.)
+            .with_getter(|s, _| s.dynamic_props.gravity_scale)
+            .with_setter(|s, _, new_val| {
+                s.dynamic_props.gravity_scale = new_val;
+                handle_or_return!(s, handle);
+                mut_world_or_return!(s, world);
+                world.rigid_body_set[handle].set_gravity_scale(new_val, true);
+            })
+            .done();
+        builder
+            .property("ccd_enabled")
+            .with_default(false)
+            .with_getter(|s, _| s.dynamic_props.ccd_enabled)
+            .with_setter(|s, _, new_val| {
+                s.dynamic_props.ccd_enabled = new_val;
+                handle_or_return!(s, handle);
+                mut_world_or_return!(s, world);
+                world.rigid_body_set[handle].enable_ccd(new_val);
+            })
+            .done();
+        builder
+            .property("can_sleep")
+            .with_default(true)
+            .with_getter(|s, _| s.dynamic_props.can_sleep)
+            .with_setter(|s, _, new_val| {
+                s.dynamic_props.can_sleep = new_val;
+                handle_or_return!(s, handle);
+                mut_world_or_return!(s, world);
+                let body = &mut world.rigid_body_set[handle];
+                let activation = body.activation_mut();
+                (activation.linear_threshold, activation.angular_threshold) =
+                    s.dynamic_props.sleep_thresholds();
+                if !new_val {
+                    body.wake_up(true);
+                }
+            })
+            .done();
+        builder
+            .property("dominance_group")
+            .with_default(0)
+            .with_hint(IntHint::Range(RangeHint::new(-127, 127)))
+            .with_getter(|s, _| s.dynamic_props.dominance_group)
+            .with_setter(|s, _, new_val| {
+                s.dynamic_props.dominance_group = new_val;
+                handle_or_return!(s, handle);
+                mut_world_or_return!(s, world);
+                world.rigid_body_set[handle].set_dominance_group(s.dynamic_props.dominance_group());
+            })
+            .done();
+        builder
+            .property("lock_rotation")
+            .with_default(false)
+            .with_getter(|s, _| s.dynamic_props.lock_rotation)
+            .with_setter(|s, _, new_val| {
+                s.dynamic_props.lock_rotation = new_val;
+                handle_or_return!(s, handle);
+                mut_world_or_return!(s, world);
+                world.rigid_body_set[handle].set_locked_axes(s.dynamic_props.locked_axes(), true);
+            })
+            .done();
+        builder
+            .property("lock_translation_x")
+            .with_default(false)
+            .with_getter(|s, _| s.dynamic_props.lock_translation_x)
+            .with_setter(|s, _, new_val| {
+                s.dynamic_props.lock_translation_x = new_val;
+                handle_or_return!(s, handle);
+                mut_world_or_return!(s, world);
+                world.rigid_body_set[handle].set_locked_axes(s.dynamic_props.locked_axes(), true);
+            })
+            .done();
+        builder
+            .property("lock_translation_y")
+            .with_default(false)
+            .with_getter(|s, _| s.dynamic_props.lock_translation_y)
+            .with_setter(|s, _, new_val| {
+                s.dynamic_props.lock_translation_y = new_val;
+                handle_or_return!(s, handle);
+                mut_world_or_return!(s, world);
+                world.rigid_body_set[handle].set_locked_axes(s.dynamic_props.locked_axes(), true);
+            })
+            .done();
+        builder
+            .property("initial_linear_velocity")
+            .with_default(Vector2::ZERO)
+            .with_getter(|s, _| s.dynamic_props.linear_velocity)
+            .with_setter(|s, _, new_val| {
+                // only applied when the body is inserted.
+                s.dynamic_props.linear_velocity = new_val;
+            })
+            .done();
+        builder
+            .property("initial_angular_velocity")
+            .with_default(0.)
+            .with_getter(|s, _| s.dynamic_props.angular_velocity)
+            .with_setter(|s, _, new_val| {
+                // only applied when the body is inserted.
+                s.dynamic_props.angular_velocity = new_val;
+            })
+            .done();
+        register_emitted_signals!(builder);
     }
 }
 impl GodotRapier2DKinematicPositionBasedBody {
+    // the node is animated, and the body follows along.
+    const FOLLOWS_NODE: bool = true;
+
     pub fn build_rigid_body(&self, base: TRef<'_, Node2D>, ppm: PixelsPerMeter) -> r2d::RigidBody {
         complete_body!(
             self,
@@ -119,25 +489,216 @@ impl GodotRapier2DKinematicPositionBasedBody {
             r2d::RigidBodyBuilder::kinematic_position_based()
         )
     }
+
+    fn register_properties(builder: &ClassBuilder<GodotRapier2DKinematicPositionBasedBody>) {
+        register_emitted_signals!(builder);
+    }
 }
 impl GodotRapier2DKinematicVelocityBasedBody {
+    const FOLLOWS_NODE: bool = false;
+
     pub fn build_rigid_body(&self, base: TRef<'_, Node2D>, ppm: PixelsPerMeter) -> r2d::RigidBody {
         complete_body!(
             self,
             base,
             ppm,
             r2d::RigidBodyBuilder::kinematic_velocity_based()
+                .linvel(self.velocity_props.linvel(ppm))
+                .angvel(self.velocity_props.angular_velocity)
         )
     }
+
+    fn register_properties(builder: &ClassBuilder<GodotRapier2DKinematicVelocityBasedBody>) {
+        builder
+            .property("linear_velocity")
+            .with_default(Vector2::ZERO)
+            .with_getter(|s, _| s.velocity_props.linear_velocity)
+            .with_setter(|s, _, new_val| {
+                s.velocity_props.linear_velocity = new_val;
+                let Some(ppm) = s.world_props.world_ppm else {return};
+                handle_or_return!(s, handle);
+                mut_world_or_return!(s, world);
+                world.rigid_body_set[handle].set_linvel(s.velocity_props.linvel(ppm), true);
+            })
+            .done();
+        builder
+            .property("angular_velocity")
+            .with_default(0.)
+            .with_getter(|s, _| s.velocity_props.angular_velocity)
+            .with_setter(|s, _, new_val| {
+                s.velocity_props.angular_velocity = new_val;
+                handle_or_return!(s, handle);
+                mut_world_or_return!(s, world);
+                world.rigid_body_set[handle].set_angvel(new_val, true);
+            })
+            .done();
+        register_emitted_signals!(builder);
+    }
 }
 
+impl GodotRapier2DCharacterBody {
+    // `move_and_slide` moves the node, and the body follows along.
+    const FOLLOWS_NODE: bool = true;
+
+    pub fn build_rigid_body(&self, base: TRef<'_, Node2D>, ppm: PixelsPerMeter) -> r2d::RigidBody {
+        complete_body!(
+            self,
+            base,
+            ppm,
+            r2d::RigidBodyBuilder::kinematic_position_based()
+        )
+    }
+
+    // moves the node by as much of `velocity` over one physics step as its surroundings allow,
+    // returning the velocity it effectively moved with.
+    fn slide(&mut self, base: TRef<'_, Node2D>, velocity: Vector2) -> Option<Vector2> {
+        use rapier2d::prelude::*;
+        let handle = self.world_props.handle?;
+        let ppm = self.world_props.world_ppm?;
+        let world = self
+            .world_props
+            .world_ref
+            .as_ref()
+            .and_then(Weak::upgrade)?;
+        let world = world.borrow();
+
+        // the character is shaped by its first collider.
+        let collider_handle = *world.rigid_body_set.get(handle)?.colliders().first()?;
+        let collider = world.collider_set.get(collider_handle)?;
+
+        // the node may have been moved since the last step, so the shape is placed relative to it.
+        let position = base.global_position();
+        let character_pos = Isometry::new(
+            vector![position.x / ppm.0, position.y / ppm.0],
+            base.global_rotation() as f32,
+        ) * collider
+            .position_wrt_parent()
+            .copied()
+            .unwrap_or_else(Isometry::identity);
+        let filter = QueryFilter::new()
+            .exclude_rigid_body(handle)
+            .exclude_sensors()
+            .groups(collider.collision_groups());
+
+        let controller = self.character_props.controller(ppm);
+        let dt = world.integration_parameters.dt;
+        let mut collisions = vec![];
+        let movement = controller.move_shape(
+            dt,
+            &world.rigid_body_set,
+            &world.collider_set,
+            &world.query_pipeline,
+            collider.shape(),
+            &character_pos,
+            vector![velocity.x / ppm.0, velocity.y / ppm.0] * dt,
+            filter,
+            |c| collisions.push(c),
+        );
+
+        // walls are too steep to climb, without hanging over the character like a ceiling.
+        let is_wall = |normal: &Vector<Real>| {
+            let angle = normal.angle(&controller.up);
+            angle > controller.max_slope_climb_angle
+                && angle < PI - controller.max_slope_climb_angle
+        };
+        self.character_state.on_floor = movement.grounded;
+        self.character_state.on_wall = collisions.iter().any(|c| is_wall(&c.toi.normal1));
+        self.character_state.collisions = collisions
+            .iter()
+            .map(|c| {
+                let info = Dictionary::new();
+                info.insert("collider", world.nodes.collider_node(c.handle));
+                info.insert(
+                    "position",
+                    Vector2 {
+                        x: c.toi.witness1.x * ppm.0,
+                        y: c.toi.witness1.y * ppm.0,
+                    },
+                );
+                info.insert(
+                    "normal",
+                    Vector2 {
+                        x: c.toi.normal1.x,
+                        y: c.toi.normal1.y,
+                    },
+                );
+                info.insert(
+                    "travel",
+                    Vector2 {
+                        x: c.translation_applied.x * ppm.0,
+                        y: c.translation_applied.y * ppm.0,
+                    },
+                );
+                info.insert(
+                    "remainder",
+                    Vector2 {
+                        x: c.translation_remaining.x * ppm.0,
+                        y: c.translation_remaining.y * ppm.0,
+                    },
+                );
+                info.into_shared()
+            })
+            .collect();
+
+        let translation = Vector2 {
+            x: movement.translation.x * ppm.0,
+            y: movement.translation.y * ppm.0,
+        };
+        base.set_global_position(position + translation);
+        Some(translation / dt)
+    }
+
+    fn register_properties(builder: &ClassBuilder<GodotRapier2DCharacterBody>) {
+        builder
+            .property("max_slope_climb_angle")
+            .with_default(FRAC_PI_4)
+            .with_hint(FloatHint::Range(
+                RangeHint::new(0.0, FRAC_PI_2).with_step(0.01),
+            ))
+            .with_getter(|s, _| s.character_props.max_slope_climb_angle)
+            .with_setter(|s, _, new_val| s.character_props.max_slope_climb_angle = new_val)
+            .done();
+        builder
+            .property("min_slope_slide_angle")
+            .with_default(FRAC_PI_4)
+            .with_hint(FloatHint::Range(
+                RangeHint::new(0.0, FRAC_PI_2).with_step(0.01),
+            ))
+            .with_getter(|s, _| s.character_props.min_slope_slide_angle)
+            .with_setter(|s, _, new_val| s.character_props.min_slope_slide_angle = new_val)
+            .done();
+        builder
+            .property("autostep_max_height")
+            .with_default(0.)
+            .with_getter(|s, _| s.character_props.autostep_max_height)
+            .with_setter(|s, _, new_val| s.character_props.autostep_max_height = new_val)
+            .done();
+        builder
+            .property("autostep_min_width")
+            .with_default(0.)
+            .with_getter(|s, _| s.character_props.autostep_min_width)
+            .with_setter(|s, _, new_val| s.character_props.autostep_min_width = new_val)
+            .done();
+        builder
+            .property("snap_to_ground")
+            .with_default(0.)
+            .with_getter(|s, _| s.character_props.snap_to_ground)
+            .with_setter(|s, _, new_val| s.character_props.snap_to_ground = new_val)
+            .done();
+        register_emitted_signals!(builder);
+    }
+}
+
+// trailing idents name further props fields, which start off with their defaults.
+// methods following a semicolon are exported along with the common ones.
 macro_rules! gen_godot_impl {
-    ($t:ty) => {
+    ($t:ty $(, $props:ident)* $(; $($methods:tt)*)?) => {
         #[methods]
         impl $t {
             fn new(_base: &Node2D) -> Self {
                 Self {
                     world_props: WorldProps::default(),
+                    $($props: Default::default(),)*
                 }
             }
 
@@ -170,8 +731,26 @@ macro_rules! gen_godot_impl {
                 };
                 let Some(ppm) = self.world_props.world_ppm else {return};
 
-                let world = (*world_ref).borrow();
-                let Some(body) = world.rigid_body_set.get(handle) else {return};
+                let mut world = (*world_ref).borrow_mut();
+                let Some(body) = world.rigid_body_set.get_mut(handle) else {return};
+
+                if Self::FOLLOWS_NODE {
+                    // reaching the node's pose over one step gives the body the velocity to push others along.
+                    use rapier2d::prelude::*;
+                    let position = base.global_position();
+                    let pose = Isometry::new(
+                        vector![position.x / ppm.0, position.y / ppm.0],
+                        base.global_rotation() as f32,
+                    );
+                    let moved = *body.position() != pose;
+                    body.set_next_kinematic_position(pose);
+                    // attached sensors only need rebuilding once the node has actually moved.
+                    if moved {
+                        world.mark_attached_sensors_stale(handle);
+                    }
+                    return;
+                }
+
                 let iso = body.position();
 
                 // copy rapier isometry onto godot object.
@@ -191,11 +770,15 @@ macro_rules! gen_godot_impl {
                     macro_rules! register_collider {
                         ($i:ident) => {{
                             $i.map_mut(move |n, b| {
-                                let c = n.build_collider(b, ppm);
+                                // shapes that cannot be built are left out of the world, until they can.
+                                let Some(c) = n.build_collider(b, ppm) else {
+                                    n.defer_to_world(&world_ref, parent_handle, ppm);
+                                    return
+                                };
                                 let handle = world_ref
                                     .borrow_mut()
                                     .insert_collider_with_parent(c, parent_handle);
-                                n.add_to_world(base, &world_ref, handle, ppm)
+                                n.add_to_world(b, &world_ref, handle, ppm)
                             })
                             .unwrap();
                             n.connect(
@@ -213,6 +796,28 @@ macro_rules! gen_godot_impl {
                         register_collider!(i)
                     } else if let Some(i) = n.cast_instance::<GodotRapier2DColliderCuboid>() {
                         register_collider!(i)
+                    } else if let Some(i) = n.cast_instance::<GodotRapier2DColliderCapsule>() {
+                        register_collider!(i)
+                    } else if let Some(i) = n.cast_instance::<GodotRapier2DColliderSegment>() {
+                        register_collider!(i)
+                    } else if let Some(i) = n.cast_instance::<GodotRapier2DColliderTriangle>() {
+                        register_collider!(i)
+                    } else if let Some(i) = n.cast_instance::<GodotRapier2DColliderPolygon>() {
+                        register_collider!(i)
+                    } else if let Some(i) = n.cast_instance::<GodotRapier2DColliderHeightfield>() {
+                        register_collider!(i)
+                    } else if let Some(i) = n.cast_instance::<GodotRapier2DRayCast>() {
+                        // rays insert nothing, but may leave out the body they are cast from.
+                        i.map_mut(move |n, b| n.add_to_world(b, &world_ref, Some(parent_handle), ppm))
+                            .unwrap();
+                        n.connect(
+                            "tree_exiting",
+                            base,
+                            "_unregister_child",
+                            (VariantArray::from_iter(vec![node])).into_shared(),
+                            Object::CONNECT_ONESHOT,
+                        )
+                        .unwrap();
                     }
             }
 
@@ -232,14 +837,122 @@ macro_rules! gen_godot_impl {
                         unregister_collider!(i)
                     } else if let Some(i) = n2d.cast_instance::<GodotRapier2DColliderCuboid>() {
                         unregister_collider!(i)
+                    } else if let Some(i) = n2d.cast_instance::<GodotRapier2DColliderCapsule>() {
+                        unregister_collider!(i)
+                    } else if let Some(i) = n2d.cast_instance::<GodotRapier2DColliderSegment>() {
+                        unregister_collider!(i)
+                    } else if let Some(i) = n2d.cast_instance::<GodotRapier2DColliderTriangle>() {
+                        unregister_collider!(i)
+                    } else if let Some(i) = n2d.cast_instance::<GodotRapier2DColliderPolygon>() {
+                        unregister_collider!(i)
+                    } else if let Some(i) = n2d.cast_instance::<GodotRapier2DColliderHeightfield>() {
+                        unregister_collider!(i)
+                    } else if let Some(i) = n2d.cast_instance::<GodotRapier2DRayCast>() {
+                        unregister_collider!(i)
                     }
                 }
             }
+
+            $($($methods)*)?
         }
     }
 }
 
 gen_godot_impl!(GodotRapier2DFixedBody);
-gen_godot_impl!(GodotRapier2DDynamicBody);
+gen_godot_impl!(GodotRapier2DDynamicBody, dynamic_props;
+    // in pixel units, applied at a global position in pixels, or else at the center of mass.
+    #[method]
+    fn apply_impulse(&self, impulse: Vector2, #[opt] at_point: Option<Vector2>) {
+        use rapier2d::prelude::*;
+        self.with_body(|b, ppm| {
+            let impulse = vector![impulse.x / ppm.0, impulse.y / ppm.0];
+            match at_point {
+                Some(p) => {
+                    b.apply_impulse_at_point(impulse, point![p.x / ppm.0, p.y / ppm.0], true)
+                }
+                None => b.apply_impulse(impulse, true),
+            }
+        });
+    }
+
+    // in pixel units, keeps acting on every step until `reset_forces`.
+    #[method]
+    fn apply_force(&self, force: Vector2) {
+        use rapier2d::prelude::*;
+        self.with_body(|b, ppm| b.add_force(vector![force.x / ppm.0, force.y / ppm.0], true));
+    }
+
+    #[method]
+    fn reset_forces(&self) {
+        self.with_body(|b, _| {
+            b.reset_forces(true);
+            b.reset_torques(true)
+        });
+    }
+
+    // in pixel units, like the torques joints break at.
+    #[method]
+    fn apply_torque_impulse(&self, torque_impulse: f32) {
+        self.with_body(|b, ppm| b.apply_torque_impulse(torque_impulse / (ppm.0 * ppm.0), true));
+    }
+
+    // in pixels per second.
+    #[method]
+    fn set_linear_velocity(&self, velocity: Vector2) {
+        use rapier2d::prelude::*;
+        self.with_body(|b, ppm| {
+            b.set_linvel(vector![velocity.x / ppm.0, velocity.y / ppm.0], true)
+        });
+    }
+
+    // in pixels per second.
+    #[method]
+    fn get_linear_velocity(&self) -> Vector2 {
+        self.with_body(|b, ppm| Vector2 {
+            x: b.linvel().x * ppm.0,
+            y: b.linvel().y * ppm.0,
+        })
+        .unwrap_or(Vector2::ZERO)
+    }
+
+    // in radians per second.
+    #[method]
+    fn get_angular_velocity(&self) -> f32 {
+        self.with_body(|b, _| b.angvel()).unwrap_or(0.)
+    }
+
+    #[method]
+    fn wake_up(&self) {
+        self.with_body(|b, _| b.wake_up(true));
+    }
+
+    #[method]
+    fn sleep(&self) {
+        self.with_body(|b, _| b.sleep());
+    }
+);
 gen_godot_impl!(GodotRapier2DKinematicPositionBasedBody);
-gen_godot_impl!(GodotRapier2DKinematicVelocityBasedBody);
+gen_godot_impl!(GodotRapier2DKinematicVelocityBasedBody, velocity_props);
+gen_godot_impl!(GodotRapier2DCharacterBody, character_props, character_state;
+    // in pixels per second.
+    #[method]
+    fn move_and_slide(&mut self, #[base] base: TRef<'_, Node2D>, velocity: Vector2) -> Vector2 {
+        self.slide(base, velocity).unwrap_or(Vector2::ZERO)
+    }
+
+    #[method]
+    fn is_on_floor(&self) -> bool {
+        self.character_state.on_floor
+    }
+
+    #[method]
+    fn is_on_wall(&self) -> bool {
+        self.character_state.on_wall
+    }
+
+    // collisions met during the last `move_and_slide`, in pixel units.
+    #[method]
+    fn get_slide_collisions(&self) -> VariantArray {
+        VariantArray::from_iter(&self.character_state.collisions).into_shared()
+    }
+);
@@ -1,12 +1,15 @@
 use std::{
     cell::RefCell,
+    collections::HashSet,
     rc::{Rc, Weak},
 };
 
+use gdnative::export::hint::{EnumHint, FloatHint, IntHint, RangeHint};
 use gdnative::prelude::*;
+use rapier2d::parry::transformation::vhacd::VHACDParameters;
 use rapier2d::prelude::{self as r2d, ColliderBuilder};
 
-use super::common::{PixelsPerMeter, World2DAware, World2DState};
+use super::common::{should_draw, PixelsPerMeter, World2DAware, World2DState};
 
 macro_rules! shape_color {
     () => {
@@ -14,23 +17,77 @@ macro_rules! shape_color {
     };
 }
 
-macro_rules! should_draw {
-    ($base : ident) => {{
-        use gdnative::api::Engine;
-        Engine::godot_singleton().is_editor_hint()
-            || $base
-                .get_tree()
-                .and_then(|t| unsafe { t.assume_safe_if_sane() })
-                .map(|a| a.is_debugging_collisions_hint())
-                .unwrap_or(true)
-    }};
-}
-
 #[derive(Default)]
 struct WorldProps {
     world_ref: Option<Weak<RefCell<World2DState>>>,
     world_ppm: Option<PixelsPerMeter>,
     handle: Option<r2d::ColliderHandle>,
+    // body of a collider left out of the world for its shape, until it can be built.
+    parent: Option<r2d::RigidBodyHandle>,
+}
+
+impl WorldProps {
+    // attaches a collider that was left out of the world to the body that registered it.
+    fn insert_deferred(&mut self, base: TRef<'_, Node2D>, collider: r2d::Collider) {
+        let Some(parent) = self.parent.take() else {return};
+        let Some(world) = self.world_ref.as_ref().and_then(Weak::upgrade) else {return};
+        let mut world = world.borrow_mut();
+        // the body may have left the world since.
+        if !world.rigid_body_set.contains(parent) {
+            return;
+        }
+        let handle = world.insert_collider_with_parent(collider, parent);
+        world.nodes.insert_collider(handle, base);
+        self.handle = Some(handle);
+        base.update()
+    }
+}
+
+// overlaps of the collider while it is a sensor, as tracked by the world.
+impl WorldProps {
+    fn overlapping_colliders(&self) -> VariantArray {
+        let colliders = VariantArray::new();
+        let Some(handle) = self.handle else {return colliders.into_shared()};
+        let Some(world) = self.world_ref.as_ref().and_then(Weak::upgrade) else {return colliders.into_shared()};
+        let world = world.borrow();
+        world
+            .overlapping_colliders(handle)
+            .filter_map(|c| world.nodes.collider_node(c))
+            .for_each(|n| colliders.push(n));
+        colliders.into_shared()
+    }
+
+    // each body only once, however many of its colliders overlap.
+    fn overlapping_bodies(&self) -> VariantArray {
+        let bodies = VariantArray::new();
+        let Some(handle) = self.handle else {return bodies.into_shared()};
+        let Some(world) = self.world_ref.as_ref().and_then(Weak::upgrade) else {return bodies.into_shared()};
+        let world = world.borrow();
+        let handles: HashSet<_> = world
+            .overlapping_colliders(handle)
+            .filter_map(|c| world.collider_set.get(c)?.parent())
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|b| world.nodes.body_node(b))
+            .for_each(|n| bodies.push(n));
+        bodies.into_shared()
+    }
+
+    // whether `node` is an overlapping collider, or a body one of them is attached to.
+    fn overlaps(&self, node: TRef<'_, Node2D>) -> bool {
+        let Some(handle) = self.handle else {return false};
+        let Some(world) = self.world_ref.as_ref().and_then(Weak::upgrade) else {return false};
+        let world = world.borrow();
+        let collider = world.nodes.collider_handle(node);
+        let body = world.nodes.body_handle(node);
+        world.overlapping_colliders(handle).any(|c| {
+            Some(c) == collider
+                || body.is_some_and(|b| {
+                    world.collider_set.get(c).and_then(r2d::Collider::parent) == Some(b)
+                })
+        })
+    }
 }
 
 // receive collider handle or abort current routine.
@@ -64,22 +121,40 @@ macro_rules! proxy_world_awareness_to_world_props {
                 self.world_props.world_ref = Some(Rc::downgrade(world_ref));
                 self.world_props.world_ppm = Some(ppm);
                 self.world_props.handle = Some(handle);
+                world_ref.borrow_mut().nodes.insert_collider(handle, base);
                 base.update()
             }
 
             fn remove_from_world(&mut self, base: TRef<'_, Node2D>) {
                 if let Some(world) = self.world_props.world_ref.as_ref().and_then(Weak::upgrade) {
                     if let Some(handle) = self.world_props.handle {
-                        world.borrow_mut().remove_collider(handle)
+                        let mut world = world.borrow_mut();
+                        world.nodes.remove_collider(handle);
+                        world.remove_collider(handle)
                     }
                 };
 
                 self.world_props.world_ref = None;
                 self.world_props.handle = None;
                 self.world_props.world_ppm = None;
+                self.world_props.parent = None;
                 base.update();
             }
         }
+
+        impl $t {
+            // keeps track of the world and body of a collider whose shape cannot be built yet.
+            pub fn defer_to_world(
+                &mut self,
+                world_ref: &super::common::World2DRef,
+                parent: r2d::RigidBodyHandle,
+                ppm: PixelsPerMeter,
+            ) {
+                self.world_props.world_ref = Some(Rc::downgrade(world_ref));
+                self.world_props.world_ppm = Some(ppm);
+                self.world_props.parent = Some(parent);
+            }
+        }
     };
 }
 
@@ -89,6 +164,12 @@ struct ColliderProps {
     density: f32,
     restitution: f32,
     is_sensor: bool,
+    // in pixel units, like the magnitude emitted with `contact_force`. 0 disables the signal.
+    contact_force_threshold: f32,
+    collision_layer: u32,
+    collision_mask: u32,
+    solver_layer: u32,
+    solver_mask: u32,
 }
 impl Default for ColliderProps {
     fn default() -> Self {
@@ -97,16 +178,60 @@ impl Default for ColliderProps {
             density: ColliderBuilder::default_density(),
             restitution: 0.0,
             is_sensor: false,
+            contact_force_threshold: 0.0,
+            collision_layer: 1,
+            collision_mask: 1,
+            solver_layer: 1,
+            solver_mask: 1,
         }
     }
 }
+impl ColliderProps {
+    fn collision_groups(&self) -> r2d::InteractionGroups {
+        r2d::InteractionGroups::new(
+            r2d::Group::from_bits_truncate(self.collision_layer),
+            r2d::Group::from_bits_truncate(self.collision_mask),
+        )
+    }
+
+    // resting contacts would otherwise report their forces on every step.
+    fn active_events(&self) -> r2d::ActiveEvents {
+        if self.contact_force_threshold > 0. && self.contact_force_threshold.is_finite() {
+            r2d::ActiveEvents::all()
+        } else {
+            r2d::ActiveEvents::COLLISION_EVENTS
+        }
+    }
+
+    // pairs passing the collision groups but not the solver groups report contacts without a physical response.
+    fn solver_groups(&self) -> r2d::InteractionGroups {
+        r2d::InteractionGroups::new(
+            r2d::Group::from_bits_truncate(self.solver_layer),
+            r2d::Group::from_bits_truncate(self.solver_mask),
+        )
+    }
+}
 
 macro_rules! register_emitted_signals {
     ($builder:ident) => {
         $builder.signal("collider_entered").with_param_untyped("collider").done();
         $builder.signal("collider_exited").with_param_untyped("collider").done();
-        $builder.signal("collision_started").with_param_untyped("collider").done();
-        $builder.signal("collision_ended").with_param_untyped("collider").done();
+        $builder
+            .signal("collision_started")
+            .with_param_untyped("collider")
+            .with_param("collision_info", VariantType::Dictionary)
+            .done();
+        $builder
+            .signal("collision_ended")
+            .with_param_untyped("collider")
+            .with_param("collision_info", VariantType::Dictionary)
+            .done();
+        $builder
+            .signal("contact_force")
+            .with_param_untyped("collider")
+            .with_param("total_force_magnitude", VariantType::F64)
+            .with_param("max_force_direction", VariantType::Vector2)
+            .done();
     };
 }
 
@@ -157,13 +282,79 @@ macro_rules! proxy_properties_to_collider_props {
                 handle_or_return!(s, handle);
                 mut_world_or_return!(s, world);
                 world.collider_set[handle].set_sensor(new_val);
+                world.mark_sensor_stale(handle);
+            })
+            .done();
+        $builder
+            .property("contact_force_threshold")
+            .with_default(0.)
+            .with_getter(|s, _| s.collider_props.contact_force_threshold)
+            .with_setter(|s, _, new_val| {
+                s.collider_props.contact_force_threshold = new_val;
+                let Some(ppm) = s.world_props.world_ppm else {return};
+                handle_or_return!(s, handle);
+                mut_world_or_return!(s, world);
+                let collider = &mut world.collider_set[handle];
+                collider.set_contact_force_event_threshold(new_val / ppm.0);
+                collider.set_active_events(s.collider_props.active_events());
+            })
+            .done();
+        $builder
+            .property("collision_layer")
+            .with_default(1)
+            .with_hint(IntHint::Layers2DPhysics)
+            .with_getter(|s, _| s.collider_props.collision_layer)
+            .with_setter(|s, _, new_val| {
+                s.collider_props.collision_layer = new_val;
+                handle_or_return!(s, handle);
+                mut_world_or_return!(s, world);
+                world.collider_set[handle]
+                    .set_collision_groups(s.collider_props.collision_groups());
+                world.mark_sensor_stale(handle);
+            })
+            .done();
+        $builder
+            .property("collision_mask")
+            .with_default(1)
+            .with_hint(IntHint::Layers2DPhysics)
+            .with_getter(|s, _| s.collider_props.collision_mask)
+            .with_setter(|s, _, new_val| {
+                s.collider_props.collision_mask = new_val;
+                handle_or_return!(s, handle);
+                mut_world_or_return!(s, world);
+                world.collider_set[handle]
+                    .set_collision_groups(s.collider_props.collision_groups());
+                world.mark_sensor_stale(handle);
+            })
+            .done();
+        $builder
+            .property("solver_layer")
+            .with_default(1)
+            .with_hint(IntHint::Layers2DPhysics)
+            .with_getter(|s, _| s.collider_props.solver_layer)
+            .with_setter(|s, _, new_val| {
+                s.collider_props.solver_layer = new_val;
+                handle_or_return!(s, handle);
+                mut_world_or_return!(s, world);
+                world.collider_set[handle].set_solver_groups(s.collider_props.solver_groups());
+            })
+            .done();
+        $builder
+            .property("solver_mask")
+            .with_default(1)
+            .with_hint(IntHint::Layers2DPhysics)
+            .with_getter(|s, _| s.collider_props.solver_mask)
+            .with_setter(|s, _, new_val| {
+                s.collider_props.solver_mask = new_val;
+                handle_or_return!(s, handle);
+                mut_world_or_return!(s, world);
+                world.collider_set[handle].set_solver_groups(s.collider_props.solver_groups());
             })
             .done();
     };
 }
 
 // bridges common props towards collider builder.
-// stores a raw pointer box to reference of `base` on the heap, which must be dropped eventually.
 macro_rules! complete_collider {
     ($self:ident,$base:ident,$ppm:ident,$builder:expr) => {{
         use rapier2d::prelude::*;
@@ -177,12 +368,41 @@ macro_rules! complete_collider {
             .density($self.collider_props.density)
             .restitution($self.collider_props.restitution)
             .sensor($self.collider_props.is_sensor)
-            .user_data(&$base.claim() as *const Ref<Node2D> as u128)
-            .active_events(r2d::ActiveEvents::all())
+            .contact_force_event_threshold($self.collider_props.contact_force_threshold / $ppm.0)
+            .collision_groups($self.collider_props.collision_groups())
+            .solver_groups($self.collider_props.solver_groups())
+            .active_events($self.collider_props.active_events())
             .build()
     }};
 }
 
+// exports the methods common to all colliders along with the class's own.
+macro_rules! gen_collider_impl {
+    ($t:ty; $($methods:tt)*) => {
+        #[methods]
+        impl $t {
+            $($methods)*
+
+            #[method]
+            fn get_overlapping_colliders(&self) -> VariantArray {
+                self.world_props.overlapping_colliders()
+            }
+
+            #[method]
+            fn get_overlapping_bodies(&self) -> VariantArray {
+                self.world_props.overlapping_bodies()
+            }
+
+            #[method]
+            fn overlaps(&self, node: Ref<Node2D>) -> bool {
+                // freed nodes overlap nothing.
+                let Some(node) = (unsafe { node.assume_safe_if_sane() }) else {return false};
+                self.world_props.overlaps(node)
+            }
+        }
+    };
+}
+
 // --------------------------------
 // Circle
 
@@ -196,8 +416,7 @@ pub struct GodotRapier2DColliderBall {
 }
 proxy_world_awareness_to_world_props!(GodotRapier2DColliderBall);
 
-#[methods]
-impl GodotRapier2DColliderBall {
+gen_collider_impl!(GodotRapier2DColliderBall;
     pub fn new(_base: &Node2D) -> Self {
         Self {
             world_props: WorldProps::default(),
@@ -206,8 +425,17 @@ impl GodotRapier2DColliderBall {
         }
     }
 
-    pub fn build_collider(&self, base: TRef<'_, Node2D>, ppm: PixelsPerMeter) -> r2d::Collider {
-        complete_collider!(self, base, ppm, r2d::ColliderBuilder::ball(self.radius))
+    pub fn build_collider(
+        &self,
+        base: TRef<'_, Node2D>,
+        ppm: PixelsPerMeter,
+    ) -> Option<r2d::Collider> {
+        Some(complete_collider!(
+            self,
+            base,
+            ppm,
+            r2d::ColliderBuilder::ball(self.radius)
+        ))
     }
 
     fn register_properties(builder: &ClassBuilder<GodotRapier2DColliderBall>) {
@@ -241,7 +469,7 @@ impl GodotRapier2DColliderBall {
             );
         }
     }
-}
+);
 
 // --------------------------------
 // Cuboid
@@ -255,8 +483,7 @@ pub struct GodotRapier2DColliderCuboid {
     half_extents: Vector2,
 }
 proxy_world_awareness_to_world_props!(GodotRapier2DColliderCuboid);
-#[methods]
-impl GodotRapier2DColliderCuboid {
+gen_collider_impl!(GodotRapier2DColliderCuboid;
     pub fn new(_base: &Node2D) -> Self {
         Self {
             collider_props: ColliderProps::default(),
@@ -265,13 +492,17 @@ impl GodotRapier2DColliderCuboid {
         }
     }
 
-    pub fn build_collider(&self, base: TRef<'_, Node2D>, ppm: PixelsPerMeter) -> r2d::Collider {
-        complete_collider!(
+    pub fn build_collider(
+        &self,
+        base: TRef<'_, Node2D>,
+        ppm: PixelsPerMeter,
+    ) -> Option<r2d::Collider> {
+        Some(complete_collider!(
             self,
             base,
             ppm,
             ColliderBuilder::cuboid(self.half_extents.x, self.half_extents.y)
-        )
+        ))
     }
 
     fn register_properties(builder: &ClassBuilder<GodotRapier2DColliderCuboid>) {
@@ -291,6 +522,7 @@ impl GodotRapier2DColliderCuboid {
             })
             .done();
         proxy_properties_to_collider_props!(builder);
+        register_emitted_signals!(builder);
     }
 
     #[method]
@@ -315,4 +547,866 @@ impl GodotRapier2DColliderCuboid {
             );
         }
     }
+);
+
+// --------------------------------
+// Capsule
+
+// values of the capsule `orientation` enum property.
+const CAPSULE_VERTICAL: i64 = 0;
+const CAPSULE_HORIZONTAL: i64 = 1;
+
+#[derive(NativeClass)]
+#[inherit(Node2D)]
+#[register_with(Self::register_properties)]
+pub struct GodotRapier2DColliderCapsule {
+    world_props: WorldProps,
+    collider_props: ColliderProps,
+    half_height: f32,
+    radius: f32,
+    orientation: i64,
+}
+proxy_world_awareness_to_world_props!(GodotRapier2DColliderCapsule);
+gen_collider_impl!(GodotRapier2DColliderCapsule;
+    pub fn new(_base: &Node2D) -> Self {
+        Self {
+            world_props: WorldProps::default(),
+            collider_props: ColliderProps::default(),
+            half_height: 0.5,
+            radius: 0.5,
+            orientation: CAPSULE_VERTICAL,
+        }
+    }
+
+    fn capsule(&self) -> r2d::Capsule {
+        if self.orientation == CAPSULE_HORIZONTAL {
+            r2d::Capsule::new_x(self.half_height, self.radius)
+        } else {
+            r2d::Capsule::new_y(self.half_height, self.radius)
+        }
+    }
+
+    pub fn build_collider(
+        &self,
+        base: TRef<'_, Node2D>,
+        ppm: PixelsPerMeter,
+    ) -> Option<r2d::Collider> {
+        Some(complete_collider!(
+            self,
+            base,
+            ppm,
+            ColliderBuilder::new(r2d::SharedShape::new(self.capsule()))
+        ))
+    }
+
+    // re-applies all capsule properties onto the live collider shape.
+    fn update_shape(&self) {
+        handle_or_return!(self, handle);
+        mut_world_or_return!(self, world);
+        let Some(sh) = world.collider_set[handle].shape_mut().as_capsule_mut() else {return};
+        *sh = self.capsule();
+    }
+
+    fn register_properties(builder: &ClassBuilder<GodotRapier2DColliderCapsule>) {
+        builder
+            .property("half_height")
+            .with_default(0.5)
+            .with_getter(|s, _| s.half_height)
+            .with_setter(|s, b, new_val| {
+                s.half_height = new_val;
+                b.update();
+                s.update_shape();
+            })
+            .done();
+        builder
+            .property("radius")
+            .with_default(0.5)
+            .with_getter(|s, _| s.radius)
+            .with_setter(|s, b, new_val| {
+                s.radius = new_val;
+                b.update();
+                s.update_shape();
+            })
+            .done();
+        builder
+            .property("orientation")
+            .with_default(CAPSULE_VERTICAL)
+            .with_hint(IntHint::Enum(EnumHint::new(vec![
+                "Vertical".to_owned(),
+                "Horizontal".to_owned(),
+            ])))
+            .with_getter(|s, _| s.orientation)
+            .with_setter(|s, b, new_val| {
+                s.orientation = new_val;
+                b.update();
+                s.update_shape();
+            })
+            .done();
+        proxy_properties_to_collider_props!(builder);
+        register_emitted_signals!(builder);
+    }
+
+    #[method]
+    pub fn _draw(&self, #[base] base: TRef<'_, Node2D>) {
+        if should_draw!(base) {
+            let Some(ppm) = self.world_props.world_ppm else {return};
+            let outline = self
+                .capsule()
+                .to_polyline(16)
+                .iter()
+                .map(|p| Vector2 {
+                    x: p.x * ppm.0,
+                    y: p.y * ppm.0,
+                })
+                .collect();
+            base.draw_colored_polygon(
+                Vector2Array::from_vec(outline),
+                shape_color!(),
+                Vector2Array::new(),
+                Null::<Texture>::null(),
+                Null::<Texture>::null(),
+                false,
+            );
+        }
+    }
+);
+
+// --------------------------------
+// Segment
+
+#[derive(NativeClass)]
+#[inherit(Node2D)]
+#[register_with(Self::register_properties)]
+pub struct GodotRapier2DColliderSegment {
+    world_props: WorldProps,
+    collider_props: ColliderProps,
+    a: Vector2,
+    b: Vector2,
+}
+proxy_world_awareness_to_world_props!(GodotRapier2DColliderSegment);
+gen_collider_impl!(GodotRapier2DColliderSegment;
+    pub fn new(_base: &Node2D) -> Self {
+        Self {
+            world_props: WorldProps::default(),
+            collider_props: ColliderProps::default(),
+            a: Vector2 { x: -0.5, y: 0. },
+            b: Vector2 { x: 0.5, y: 0. },
+        }
+    }
+
+    pub fn build_collider(
+        &self,
+        base: TRef<'_, Node2D>,
+        ppm: PixelsPerMeter,
+    ) -> Option<r2d::Collider> {
+        use rapier2d::prelude::*;
+        Some(complete_collider!(
+            self,
+            base,
+            ppm,
+            ColliderBuilder::segment(point![self.a.x, self.a.y], point![self.b.x, self.b.y])
+        ))
+    }
+
+    fn register_properties(builder: &ClassBuilder<GodotRapier2DColliderSegment>) {
+        builder
+            .property("a")
+            .with_default(Vector2 { x: -0.5, y: 0. })
+            .with_getter(|s, _| s.a)
+            .with_setter(|s, b, new_val| {
+                s.a = new_val;
+                b.update();
+                handle_or_return!(s, handle);
+                mut_world_or_return!(s, world);
+                let Some(segment) = world.collider_set[handle].shape_mut().as_segment_mut() else {return};
+
+                use rapier2d::prelude::*;
+                segment.a = point![new_val.x, new_val.y];
+            })
+            .done();
+        builder
+            .property("b")
+            .with_default(Vector2 { x: 0.5, y: 0. })
+            .with_getter(|s, _| s.b)
+            .with_setter(|s, b, new_val| {
+                s.b = new_val;
+                b.update();
+                handle_or_return!(s, handle);
+                mut_world_or_return!(s, world);
+                let Some(segment) = world.collider_set[handle].shape_mut().as_segment_mut() else {return};
+
+                use rapier2d::prelude::*;
+                segment.b = point![new_val.x, new_val.y];
+            })
+            .done();
+        proxy_properties_to_collider_props!(builder);
+        register_emitted_signals!(builder);
+    }
+
+    #[method]
+    pub fn _draw(&self, #[base] base: TRef<'_, Node2D>) {
+        if should_draw!(base) {
+            let Some(ppm) = self.world_props.world_ppm else {return};
+            base.draw_line(self.a * ppm.0, self.b * ppm.0, shape_color!(), 2.0, false);
+        }
+    }
+);
+
+// --------------------------------
+// Triangle
+
+#[derive(NativeClass)]
+#[inherit(Node2D)]
+#[register_with(Self::register_properties)]
+pub struct GodotRapier2DColliderTriangle {
+    world_props: WorldProps,
+    collider_props: ColliderProps,
+    a: Vector2,
+    b: Vector2,
+    c: Vector2,
+}
+proxy_world_awareness_to_world_props!(GodotRapier2DColliderTriangle);
+gen_collider_impl!(GodotRapier2DColliderTriangle;
+    pub fn new(_base: &Node2D) -> Self {
+        Self {
+            world_props: WorldProps::default(),
+            collider_props: ColliderProps::default(),
+            a: Vector2 { x: 0., y: -0.5 },
+            b: Vector2 { x: 0.5, y: 0.5 },
+            c: Vector2 { x: -0.5, y: 0.5 },
+        }
+    }
+
+    pub fn build_collider(
+        &self,
+        base: TRef<'_, Node2D>,
+        ppm: PixelsPerMeter,
+    ) -> Option<r2d::Collider> {
+        use rapier2d::prelude::*;
+        Some(complete_collider!(
+            self,
+            base,
+            ppm,
+            ColliderBuilder::triangle(
+                point![self.a.x, self.a.y],
+                point![self.b.x, self.b.y],
+                point![self.c.x, self.c.y]
+            )
+        ))
+    }
+
+    // re-applies all corners onto the live collider shape.
+    fn update_shape(&self) {
+        use rapier2d::prelude::*;
+        handle_or_return!(self, handle);
+        mut_world_or_return!(self, world);
+        let Some(triangle) = world.collider_set[handle].shape_mut().as_triangle_mut() else {return};
+
+        triangle.a = point![self.a.x, self.a.y];
+        triangle.b = point![self.b.x, self.b.y];
+        triangle.c = point![self.c.x, self.c.y];
+    }
+
+    fn register_properties(builder: &ClassBuilder<GodotRapier2DColliderTriangle>) {
+        builder
+            .property("a")
+            .with_default(Vector2 { x: 0., y: -0.5 })
+            .with_getter(|s, _| s.a)
+            .with_setter(|s, b, new_val| {
+                s.a = new_val;
+                b.update();
+                s.update_shape();
+            })
+            .done();
+        builder
+            .property("b")
+            .with_default(Vector2 { x: 0.5, y: 0.5 })
+            .with_getter(|s, _| s.b)
+            .with_setter(|s, b, new_val| {
+                s.b = new_val;
+                b.update();
+                s.update_shape();
+            })
+            .done();
+        builder
+            .property("c")
+            .with_default(Vector2 { x: -0.5, y: 0.5 })
+            .with_getter(|s, _| s.c)
+            .with_setter(|s, b, new_val| {
+                s.c = new_val;
+                b.update();
+                s.update_shape();
+            })
+            .done();
+        proxy_properties_to_collider_props!(builder);
+        register_emitted_signals!(builder);
+    }
+
+    #[method]
+    pub fn _draw(&self, #[base] base: TRef<'_, Node2D>) {
+        if should_draw!(base) {
+            let Some(ppm) = self.world_props.world_ppm else {return};
+            base.draw_colored_polygon(
+                Vector2Array::from_vec(vec![self.a * ppm.0, self.b * ppm.0, self.c * ppm.0]),
+                shape_color!(),
+                Vector2Array::new(),
+                Null::<Texture>::null(),
+                Null::<Texture>::null(),
+                false,
+            );
+        }
+    }
+);
+
+// --------------------------------
+// Polygon
+
+// values of the polygon `mode` enum property.
+const POLYGON_CONVEX_HULL: i64 = 0;
+const POLYGON_CONVEX_POLYLINE: i64 = 1;
+const POLYGON_POLYLINE: i64 = 2;
+const POLYGON_TRIMESH: i64 = 3;
+const POLYGON_CONVEX_DECOMPOSITION: i64 = 4;
+
+// ear-clipping triangulation of a simple polygon, `None` if it is degenerate or self-intersecting.
+fn triangulate(vertices: &[r2d::Point<r2d::Real>]) -> Option<Vec<[u32; 3]>> {
+    if vertices.len() < 3 {
+        return None;
+    }
+    let cross = |o: r2d::Point<r2d::Real>, a: r2d::Point<r2d::Real>, b: r2d::Point<r2d::Real>| {
+        (a - o).perp(&(b - o))
+    };
+    let doubled_area: r2d::Real = (0..vertices.len())
+        .map(|i| {
+            let next = vertices[(i + 1) % vertices.len()];
+            vertices[i].coords.perp(&next.coords)
+        })
+        .sum();
+    if doubled_area.abs() <= r2d::Real::EPSILON {
+        return None;
+    }
+
+    // clip ears off a counter-clockwise ring of remaining vertex indices.
+    let mut ring: Vec<u32> = (0..vertices.len() as u32).collect();
+    if doubled_area < 0. {
+        ring.reverse();
+    }
+    let mut triangles = Vec::with_capacity(vertices.len() - 2);
+    while ring.len() > 3 {
+        let len = ring.len();
+        let corners = |i: usize| [ring[(i + len - 1) % len], ring[i], ring[(i + 1) % len]];
+        let ear = (0..len).find(|&i| {
+            let [a, b, c] = corners(i).map(|v| vertices[v as usize]);
+            cross(a, b, c) > 0.
+                && ring.iter().all(|&v| {
+                    let p = vertices[v as usize];
+                    corners(i).contains(&v)
+                        || cross(a, b, p) < 0.
+                        || cross(b, c, p) < 0.
+                        || cross(c, a, p) < 0.
+                })
+        })?;
+        triangles.push(corners(ear));
+        ring.remove(ear);
+    }
+    triangles.push([ring[0], ring[1], ring[2]]);
+    Some(triangles)
+}
+
+#[derive(NativeClass)]
+#[inherit(Node2D)]
+#[register_with(Self::register_properties)]
+pub struct GodotRapier2DColliderPolygon {
+    world_props: WorldProps,
+    collider_props: ColliderProps,
+    // in pixels, unlike the dimensions of the other collider shapes.
+    points: Vector2Array,
+    mode: i64,
+    decomposition_params: VHACDParameters,
+    // compound of convex parts along with the pixels per meter it was computed for.
+    decomposition: Option<(f32, r2d::SharedShape)>,
+}
+proxy_world_awareness_to_world_props!(GodotRapier2DColliderPolygon);
+gen_collider_impl!(GodotRapier2DColliderPolygon;
+    pub fn new(_base: &Node2D) -> Self {
+        Self {
+            world_props: WorldProps::default(),
+            collider_props: ColliderProps::default(),
+            points: Self::default_points(),
+            mode: POLYGON_CONVEX_HULL,
+            decomposition_params: VHACDParameters::default(),
+            decomposition: None,
+        }
+    }
+
+    fn default_points() -> Vector2Array {
+        Vector2Array::from_vec(vec![
+            Vector2 { x: -32., y: -32. },
+            Vector2 { x: 32., y: -32. },
+            Vector2 { x: 32., y: 32. },
+            Vector2 { x: -32., y: 32. },
+        ])
+    }
+
+    fn vertices(&self, ppm: PixelsPerMeter) -> Vec<r2d::Point<r2d::Real>> {
+        use rapier2d::prelude::*;
+        self.points
+            .read()
+            .iter()
+            .map(|p| point![p.x / ppm.0, p.y / ppm.0])
+            .collect()
+    }
+
+    // `None` if the points cannot form a shape of the current mode.
+    fn shape(&mut self, ppm: PixelsPerMeter) -> Option<r2d::SharedShape> {
+        use rapier2d::prelude::*;
+        if self.mode == POLYGON_CONVEX_DECOMPOSITION {
+            return self.decomposition(ppm);
+        }
+
+        let vertices = self.vertices(ppm);
+        match self.mode {
+            POLYGON_CONVEX_POLYLINE => SharedShape::convex_polyline(vertices),
+            POLYGON_POLYLINE => {
+                (vertices.len() >= 2).then(|| SharedShape::polyline(vertices, None))
+            }
+            POLYGON_TRIMESH => {
+                triangulate(&vertices).map(|indices| SharedShape::trimesh(vertices, indices))
+            }
+            _ => SharedShape::convex_hull(&vertices),
+        }
+    }
+
+    // runs VHACD on the closed outline, reusing the previous result while points and parameters are unchanged.
+    fn decomposition(&mut self, ppm: PixelsPerMeter) -> Option<r2d::SharedShape> {
+        use rapier2d::parry::transformation::vhacd::VHACD;
+        if let Some((cached_ppm, shape)) = &self.decomposition {
+            if *cached_ppm == ppm.0 {
+                return Some(shape.clone());
+            }
+        }
+
+        let vertices = self.vertices(ppm);
+        if vertices.len() < 3 {
+            return None;
+        }
+        let len = vertices.len() as u32;
+        let indices: Vec<[u32; 2]> = (0..len).map(|i| [i, (i + 1) % len]).collect();
+        let parts: Vec<_> = VHACD::decompose(&self.decomposition_params, &vertices, &indices, true)
+            .compute_exact_convex_hulls(&vertices, &indices)
+            .into_iter()
+            .filter_map(r2d::SharedShape::convex_polyline)
+            .map(|part| (r2d::Isometry::identity(), part))
+            .collect();
+        if parts.is_empty() {
+            return None;
+        }
+
+        let shape = r2d::SharedShape::compound(parts);
+        self.decomposition = Some((ppm.0, shape.clone()));
+        Some(shape)
+    }
+
+    pub fn build_collider(
+        &mut self,
+        base: TRef<'_, Node2D>,
+        ppm: PixelsPerMeter,
+    ) -> Option<r2d::Collider> {
+        let Some(shape) = self.shape(ppm) else {
+            godot_warn!(
+                "{}: points do not form a valid polygon, no collider is inserted.",
+                base.name()
+            );
+            return None;
+        };
+        Some(complete_collider!(
+            self,
+            base,
+            ppm,
+            ColliderBuilder::new(shape)
+        ))
+    }
+
+    // rebuilds the live collider shape from points and mode, keeping the previous one if they are invalid.
+    fn update_shape(&mut self, base: TRef<'_, Node2D>) {
+        let Some(ppm) = self.world_props.world_ppm else {return};
+        if self.world_props.parent.is_some() {
+            // a polygon left out of the world for being invalid joins it once it is not.
+            let Some(collider) = self.build_collider(base, ppm) else {return};
+            self.world_props.insert_deferred(base, collider);
+            return;
+        }
+        let Some(shape) = self.shape(ppm) else {
+            godot_warn!(
+                "{}: points do not form a valid polygon, the collider keeps its shape.",
+                base.name()
+            );
+            return;
+        };
+        handle_or_return!(self, handle);
+        mut_world_or_return!(self, world);
+        world.collider_set[handle].set_shape(shape);
+    }
+
+    fn register_properties(builder: &ClassBuilder<GodotRapier2DColliderPolygon>) {
+        builder
+            .property("points")
+            .with_default(Self::default_points())
+            .with_ref_getter(|s, _| &s.points)
+            .with_setter(|s, b, new_val| {
+                s.points = new_val;
+                s.decomposition = None;
+                b.update();
+                s.update_shape(b);
+            })
+            .done();
+        builder
+            .property("mode")
+            .with_default(POLYGON_CONVEX_HULL)
+            .with_hint(IntHint::Enum(EnumHint::new(vec![
+                "Convex Hull".to_owned(),
+                "Convex Polyline".to_owned(),
+                "Polyline".to_owned(),
+                "Trimesh".to_owned(),
+                "Convex Decomposition".to_owned(),
+            ])))
+            .with_getter(|s, _| s.mode)
+            .with_setter(|s, b, new_val| {
+                s.mode = new_val;
+                b.update();
+                s.update_shape(b);
+            })
+            .done();
+        builder
+            .property("decomposition_concavity")
+            .with_default(VHACDParameters::default().concavity)
+            .with_hint(FloatHint::Range(RangeHint::new(0.0, 1.0).with_step(0.01)))
+            .with_getter(|s, _| s.decomposition_params.concavity)
+            .with_setter(|s, b, new_val| {
+                s.decomposition_params.concavity = new_val;
+                s.decomposition = None;
+                b.update();
+                s.update_shape(b);
+            })
+            .done();
+        builder
+            .property("decomposition_resolution")
+            .with_default(VHACDParameters::default().resolution)
+            .with_hint(IntHint::Range(RangeHint::new(16, 1024).or_greater()))
+            .with_getter(|s, _| s.decomposition_params.resolution)
+            .with_setter(|s, b, new_val| {
+                s.decomposition_params.resolution = new_val;
+                s.decomposition = None;
+                b.update();
+                s.update_shape(b);
+            })
+            .done();
+        builder
+            .property("decomposition_max_convex_hulls")
+            .with_default(VHACDParameters::default().max_convex_hulls)
+            .with_hint(IntHint::Range(RangeHint::new(1, 1024).or_greater()))
+            .with_getter(|s, _| s.decomposition_params.max_convex_hulls)
+            .with_setter(|s, b, new_val| {
+                s.decomposition_params.max_convex_hulls = new_val;
+                s.decomposition = None;
+                b.update();
+                s.update_shape(b);
+            })
+            .done();
+        proxy_properties_to_collider_props!(builder);
+        register_emitted_signals!(builder);
+    }
+
+    // outlines every convex part of the cached decomposition.
+    fn draw_decomposition(&self, base: TRef<'_, Node2D>, ppm: f32, shape: &r2d::SharedShape) {
+        let Some(compound) = shape.as_compound() else {return};
+        for (iso, part) in compound.shapes() {
+            let Some(convex) = part.as_convex_polygon() else {continue};
+            let mut outline: Vec<Vector2> = convex
+                .points()
+                .iter()
+                .map(|p| iso * p)
+                .map(|p| Vector2 {
+                    x: p.x * ppm,
+                    y: p.y * ppm,
+                })
+                .collect();
+            if let Some(first) = outline.first().copied() {
+                outline.push(first);
+            }
+            base.draw_polyline(Vector2Array::from_vec(outline), shape_color!(), 2.0, false);
+        }
+    }
+
+    #[method]
+    pub fn _draw(&self, #[base] base: TRef<'_, Node2D>) {
+        if should_draw!(base) {
+            if self.mode == POLYGON_CONVEX_DECOMPOSITION {
+                if let Some((ppm, shape)) = &self.decomposition {
+                    self.draw_decomposition(base, *ppm, shape);
+                    return;
+                }
+            }
+
+            let mut outline: Vec<Vector2> = self.points.read().to_vec();
+            if self.mode == POLYGON_CONVEX_HULL {
+                let vertices: Vec<r2d::Point<r2d::Real>> =
+                    outline.iter().map(|p| r2d::Point::new(p.x, p.y)).collect();
+                outline = rapier2d::parry::transformation::convex_hull(&vertices)
+                    .iter()
+                    .map(|p| Vector2 { x: p.x, y: p.y })
+                    .collect();
+            }
+            if self.mode != POLYGON_POLYLINE {
+                if let Some(first) = outline.first().copied() {
+                    outline.push(first);
+                }
+            }
+            base.draw_polyline(Vector2Array::from_vec(outline), shape_color!(), 2.0, false);
+        }
+    }
+);
+
+// --------------------------------
+// Heightfield
+
+#[derive(NativeClass)]
+#[inherit(Node2D)]
+#[register_with(Self::register_properties)]
+pub struct GodotRapier2DColliderHeightfield {
+    world_props: WorldProps,
+    collider_props: ColliderProps,
+    // in pixels, sampled left to right and centered on the node.
+    heights: Float32Array,
+    // pixels between two neighbouring samples.
+    horizontal_scale: f32,
+}
+proxy_world_awareness_to_world_props!(GodotRapier2DColliderHeightfield);
+gen_collider_impl!(GodotRapier2DColliderHeightfield;
+    pub fn new(_base: &Node2D) -> Self {
+        Self {
+            world_props: WorldProps::default(),
+            collider_props: ColliderProps::default(),
+            heights: Float32Array::from_vec(vec![0.; 5]),
+            horizontal_scale: 32.,
+        }
+    }
+
+    // `None` for less than two samples.
+    fn heightfield(&self, ppm: PixelsPerMeter) -> Option<r2d::HeightField> {
+        use rapier2d::prelude::*;
+        let heights = self.heights.read();
+        if heights.len() < 2 {
+            return None;
+        }
+        let width = self.horizontal_scale * (heights.len() - 1) as f32;
+        Some(HeightField::new(
+            DVector::from_iterator(heights.len(), heights.iter().map(|h| h / ppm.0)),
+            vector![width / ppm.0, 1.],
+        ))
+    }
+
+    pub fn build_collider(
+        &self,
+        base: TRef<'_, Node2D>,
+        ppm: PixelsPerMeter,
+    ) -> Option<r2d::Collider> {
+        let Some(heightfield) = self.heightfield(ppm) else {
+            godot_warn!(
+                "{}: a heightfield needs at least two heights, no collider is inserted.",
+                base.name()
+            );
+            return None;
+        };
+        Some(complete_collider!(
+            self,
+            base,
+            ppm,
+            ColliderBuilder::new(r2d::SharedShape::new(heightfield))
+        ))
+    }
+
+    // replaces the live heightfield in place, keeping the collider itself and the previous shape if invalid.
+    fn update_shape(&mut self, base: TRef<'_, Node2D>) {
+        let Some(ppm) = self.world_props.world_ppm else {return};
+        if self.world_props.parent.is_some() {
+            // a heightfield left out of the world for lack of samples joins it once it has enough.
+            let Some(collider) = self.build_collider(base, ppm) else {return};
+            self.world_props.insert_deferred(base, collider);
+            return;
+        }
+        let Some(heightfield) = self.heightfield(ppm) else {
+            godot_warn!(
+                "{}: a heightfield needs at least two heights, the collider keeps its shape.",
+                base.name()
+            );
+            return;
+        };
+        handle_or_return!(self, handle);
+        mut_world_or_return!(self, world);
+        let Some(sh) = world.collider_set[handle].shape_mut().as_heightfield_mut() else {return};
+
+        *sh = heightfield;
+    }
+
+    // writes a single sample into the live heightfield, reusing its other heights and scale.
+    fn update_height(&self, index: usize, height: f32) {
+        use rapier2d::prelude::*;
+        let Some(ppm) = self.world_props.world_ppm else {return};
+        handle_or_return!(self, handle);
+        mut_world_or_return!(self, world);
+        // only copies the shape if it is shared.
+        let Some(sh) = world.collider_set[handle].shape_mut().as_heightfield_mut() else {return};
+        if index >= sh.heights().len() {
+            return;
+        }
+
+        let mut heights = sh.heights().clone();
+        heights[index] = height / ppm.0;
+        *sh = HeightField::new(heights, *sh.scale());
+    }
+
+    // rescales the live heightfield, keeping its heights.
+    fn update_scale(&self) {
+        use rapier2d::prelude::*;
+        let Some(ppm) = self.world_props.world_ppm else {return};
+        handle_or_return!(self, handle);
+        mut_world_or_return!(self, world);
+        let Some(sh) = world.collider_set[handle].shape_mut().as_heightfield_mut() else {return};
+
+        let width = self.horizontal_scale * sh.num_cells() as f32;
+        let scale = vector![width / ppm.0, 1.];
+        if sh.scale().x > 0. {
+            sh.set_scale(scale);
+        } else {
+            // a collapsed heightfield has no extent left to rescale.
+            *sh = HeightField::new(sh.heights().clone(), scale);
+        }
+    }
+
+    fn register_properties(builder: &ClassBuilder<GodotRapier2DColliderHeightfield>) {
+        builder
+            .property("heights")
+            .with_default(Float32Array::from_vec(vec![0.; 5]))
+            .with_ref_getter(|s, _| &s.heights)
+            .with_setter(|s, b, new_val| {
+                s.heights = new_val;
+                b.update();
+                s.update_shape(b);
+            })
+            .done();
+        builder
+            .property("horizontal_scale")
+            .with_default(32.)
+            .with_getter(|s, _| s.horizontal_scale)
+            .with_setter(|s, b, new_val| {
+                s.horizontal_scale = new_val;
+                b.update();
+                s.update_scale();
+            })
+            .done();
+        proxy_properties_to_collider_props!(builder);
+        register_emitted_signals!(builder);
+    }
+
+    #[method]
+    pub fn get_height(&self, index: i32) -> f32 {
+        if index < 0 || index >= self.heights.len() {
+            godot_error!("height index {} out of range.", index);
+            return 0.;
+        }
+        self.heights.get(index)
+    }
+
+    // edits a single sample, e.g. for destructible ground.
+    #[method]
+    pub fn set_height(&mut self, #[base] base: TRef<'_, Node2D>, index: i32, height: f32) {
+        if index < 0 || index >= self.heights.len() {
+            godot_error!("height index {} out of range.", index);
+            return;
+        }
+        self.heights.set(index, height);
+        base.update();
+        self.update_height(index as usize, height);
+    }
+
+    #[method]
+    pub fn _draw(&self, #[base] base: TRef<'_, Node2D>) {
+        if should_draw!(base) {
+            let heights = self.heights.read();
+            let start_x = -self.horizontal_scale * heights.len().saturating_sub(1) as f32 / 2.;
+            let outline = heights
+                .iter()
+                .enumerate()
+                .map(|(i, h)| Vector2 {
+                    x: start_x + self.horizontal_scale * i as f32,
+                    y: *h,
+                })
+                .collect();
+            base.draw_polyline(Vector2Array::from_vec(outline), shape_color!(), 2.0, false);
+        }
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::triangulate;
+    use rapier2d::prelude::*;
+
+    fn polygon(points: &[(Real, Real)]) -> Vec<Point<Real>> {
+        points.iter().map(|&(x, y)| point![x, y]).collect()
+    }
+
+    // signed areas of the triangles, positive for counter-clockwise ones.
+    fn triangle_areas(vertices: &[Point<Real>], triangles: &[[u32; 3]]) -> Vec<Real> {
+        triangles
+            .iter()
+            .map(|t| {
+                let [a, b, c] = t.map(|i| vertices[i as usize]);
+                (b - a).perp(&(c - a)) / 2.
+            })
+            .collect()
+    }
+
+    fn assert_covers(vertices: &[Point<Real>], area: Real) {
+        let triangles = triangulate(vertices).expect("polygon should triangulate");
+        assert_eq!(triangles.len(), vertices.len() - 2);
+        let areas = triangle_areas(vertices, &triangles);
+        assert!(
+            areas.iter().all(|a| *a >= 0.),
+            "inverted triangle in {:?}",
+            areas
+        );
+        assert!((areas.iter().sum::<Real>() - area).abs() < 1e-5);
+    }
+
+    #[test]
+    fn triangulates_concave_polygons() {
+        // an L shape, notched at (1, 1).
+        let vertices = polygon(&[(0., 0.), (2., 0.), (2., 1.), (1., 1.), (1., 2.), (0., 2.)]);
+        assert_covers(&vertices, 3.);
+        // a square notched down to (2, 1), leaving no ear at either top corner.
+        let vertices = polygon(&[(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]);
+        assert_covers(&vertices, 10.);
+    }
+
+    #[test]
+    fn triangulates_clockwise_polygons() {
+        let vertices = polygon(&[(0., 2.), (1., 2.), (1., 1.), (2., 1.), (2., 0.), (0., 0.)]);
+        assert_covers(&vertices, 3.);
+    }
+
+    #[test]
+    fn triangulates_polygons_with_collinear_vertices() {
+        let vertices = polygon(&[(0., 0.), (1., 0.), (2., 0.), (2., 2.), (0., 2.)]);
+        assert_covers(&vertices, 4.);
+    }
+
+    #[test]
+    fn rejects_degenerate_polygons() {
+        assert!(triangulate(&polygon(&[])).is_none());
+        assert!(triangulate(&polygon(&[(0., 0.), (1., 0.)])).is_none());
+        assert!(triangulate(&polygon(&[(0., 0.), (1., 1.), (2., 2.)])).is_none());
+        assert!(triangulate(&polygon(&[(0., 0.), (1., 0.), (1., 0.), (0., 0.)])).is_none());
+    }
 }
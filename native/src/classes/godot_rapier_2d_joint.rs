@@ -0,0 +1,750 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use gdnative::prelude::*;
+use rapier2d::prelude::{self as r2d};
+
+use super::common::{
+    not_in_editor, should_draw, JointBreakLimits, JointHandle, PixelsPerMeter, World2DAware,
+    World2DState,
+};
+
+macro_rules! joint_color {
+    () => {
+        Color::from_html("880000FF").unwrap()
+    };
+}
+
+// length in pixels of the prismatic axis drawn on either side of the anchor.
+const AXIS_DRAW_LENGTH: f32 = 32.;
+
+#[derive(Default)]
+struct WorldProps {
+    world_ref: Option<Weak<RefCell<World2DState>>>,
+    world_ppm: Option<PixelsPerMeter>,
+    handle: Option<JointHandle>,
+    // bodies the joint was inserted between, telling broken or rejected joints apart from ones removed along with a body.
+    bodies: Option<(r2d::RigidBodyHandle, r2d::RigidBodyHandle)>,
+}
+
+// receive joint handle or abort current routine.
+macro_rules! handle_or_return {
+    ($s:ident,$h:ident) => {
+        let Some($h) = $s.world_props.handle else {return};
+    };
+}
+
+// receive mutable world ref or abort current routine.
+macro_rules! mut_world_or_return {
+    ($s:ident,$i:ident) => {
+        let Some(world) = $s.world_props.world_ref.as_ref().and_then(Weak::upgrade) else {return};
+        let mut $i = world.borrow_mut();
+    };
+}
+
+// the joint itself is only inserted once both of its bodies are part of the world.
+macro_rules! proxy_world_awareness_to_world_props {
+    ($t:ty) => {
+        impl World2DAware<TRef<'_, Node2D>> for $t {
+            type Handle = ();
+
+            fn add_to_world(
+                &mut self,
+                base: TRef<'_, Node2D>,
+                world_ref: &super::common::World2DRef,
+                _handle: Self::Handle,
+                ppm: PixelsPerMeter,
+            ) {
+                self.world_props.world_ref = Some(Rc::downgrade(world_ref));
+                self.world_props.world_ppm = Some(ppm);
+                self.attach(base);
+                base.update()
+            }
+
+            fn remove_from_world(&mut self, base: TRef<'_, Node2D>) {
+                self.detach();
+
+                self.world_props.world_ref = None;
+                self.world_props.world_ppm = None;
+                base.update();
+            }
+        }
+    };
+}
+
+// common properties for joints, anchors are in pixels local to their respective body.
+#[derive(Default)]
+struct JointProps {
+    body_a: NodePath,
+    body_b: NodePath,
+    anchor_a: Vector2,
+    anchor_b: Vector2,
+    // only impulse joints break, multibody links hold regardless.
+    break_limits: JointBreakLimits,
+    // joins the bodies as a link of a reduced-coordinates multibody, bodies must then form a tree.
+    multibody: bool,
+}
+
+// motor and limits along the single free axis of revolute and prismatic joints.
+struct MotorProps {
+    motor_enabled: bool,
+    target_position: f32,
+    target_velocity: f32,
+    stiffness: f32,
+    damping: f32,
+    max_force: f32,
+    limits_enabled: bool,
+    lower_limit: f32,
+    upper_limit: f32,
+}
+impl Default for MotorProps {
+    fn default() -> Self {
+        Self {
+            motor_enabled: false,
+            target_position: 0.,
+            target_velocity: 0.,
+            stiffness: 0.,
+            damping: 1.,
+            max_force: f32::INFINITY,
+            limits_enabled: false,
+            lower_limit: 0.,
+            upper_limit: 0.,
+        }
+    }
+}
+impl MotorProps {
+    // `length_scale` converts pixel lengths into meters for linear axes,
+    // `force_scale` pixel forces, or torques for angular axes, into their unscaled counterparts.
+    fn apply(
+        &self,
+        joint: &mut r2d::GenericJoint,
+        axis: r2d::JointAxis,
+        length_scale: f32,
+        force_scale: f32,
+    ) {
+        if self.motor_enabled {
+            joint
+                .set_motor(
+                    axis,
+                    self.target_position * length_scale,
+                    self.target_velocity * length_scale,
+                    self.stiffness,
+                    self.damping,
+                )
+                .set_motor_max_force(axis, self.max_force * force_scale);
+        } else {
+            joint.motor_axes.remove(axis.into());
+        }
+
+        if self.limits_enabled {
+            joint.set_limits(
+                axis,
+                [
+                    self.lower_limit * length_scale,
+                    self.upper_limit * length_scale,
+                ],
+            );
+        } else {
+            joint.limit_axes.remove(axis.into());
+        }
+    }
+}
+
+// resolves a body path relative to the joint node.
+pub fn body_node<'a>(base: TRef<'a, Node2D>, path: &NodePath) -> Option<TRef<'a, Node2D>> {
+    base.get_node_or_null(path.new_ref())
+        .map(|n| unsafe { n.assume_safe() })
+        .and_then(|n| n.cast::<Node2D>())
+}
+
+// Convenience macro for joint structs to manage their lifetime within the world.
+macro_rules! impl_joint_attachment {
+    ($t:ty) => {
+        impl $t {
+            // inserts the joint, given that both bodies resolve to rigid bodies of the world.
+            fn attach(&mut self, base: TRef<'_, Node2D>) {
+                if self.world_props.bodies.is_some() {
+                    return;
+                }
+                let Some(ppm) = self.world_props.world_ppm else {return};
+                mut_world_or_return!(self, world);
+                let body_handle = |path: &NodePath| {
+                    body_node(base, path).and_then(|n| world.nodes.body_handle(n))
+                };
+                let Some(body_a) = body_handle(&self.joint_props.body_a) else {return};
+                let Some(body_b) = body_handle(&self.joint_props.body_b) else {return};
+
+                let joint = self.build_joint(
+                    &world.rigid_body_set[body_a],
+                    &world.rigid_body_set[body_b],
+                    ppm,
+                );
+                self.world_props.bodies = Some((body_a, body_b));
+                self.world_props.handle = if self.joint_props.multibody {
+                    match world.multibody_joints.insert(body_a, body_b, joint, true) {
+                        Some(handle) => Some(JointHandle::Multibody(handle)),
+                        None => {
+                            godot_warn!(
+                                "{}: multibody links must form a tree, with body_b not yet linked to a parent.",
+                                base.name()
+                            );
+                            None
+                        }
+                    }
+                } else {
+                    Some(JointHandle::Impulse(world.insert_impulse_joint(
+                        body_a,
+                        body_b,
+                        joint,
+                        base,
+                        self.joint_props.break_limits,
+                    )))
+                };
+            }
+
+            fn detach(&mut self) {
+                self.world_props.bodies = None;
+                let Some(handle) = self.world_props.handle.take() else {return};
+                mut_world_or_return!(self, world);
+                world.remove_joint(handle);
+            }
+
+            // rapier drops joints along with either of their bodies, so re-attach once bodies are back.
+            // joints broken apart or rejected stay so, until their bodies get reassigned.
+            fn sync_with_world(&mut self, base: TRef<'_, Node2D>) {
+                let Some(world) = self.world_props.world_ref.as_ref().and_then(Weak::upgrade) else {return};
+                let bodies_remain = self.world_props.bodies.is_some_and(|(a, b)| {
+                    let world = world.borrow();
+                    world.rigid_body_set.contains(a) && world.rigid_body_set.contains(b)
+                });
+                if bodies_remain {
+                    return;
+                }
+                self.detach();
+                self.attach(base);
+            }
+
+            fn update_break_limits(&self) {
+                let Some(JointHandle::Impulse(handle)) = self.world_props.handle else {return};
+                mut_world_or_return!(self, world);
+                if world.impulse_joints.contains(handle) {
+                    world
+                        .joint_break_limits
+                        .insert(handle, self.joint_props.break_limits);
+                }
+            }
+
+            fn update_anchors(&self) {
+                handle_or_return!(self, handle);
+                let Some(ppm) = self.world_props.world_ppm else {return};
+                mut_world_or_return!(self, world);
+                let Some(joint) = world.joint_data_mut(handle) else {return};
+                joint
+                    .set_local_anchor1(r2d::Point::new(
+                        self.joint_props.anchor_a.x / ppm.0,
+                        self.joint_props.anchor_a.y / ppm.0,
+                    ))
+                    .set_local_anchor2(r2d::Point::new(
+                        self.joint_props.anchor_b.x / ppm.0,
+                        self.joint_props.anchor_b.y / ppm.0,
+                    ));
+            }
+
+            // position of a point given local to a body, as seen from the joint node.
+            fn to_joint_local(
+                base: TRef<'_, Node2D>,
+                body: &NodePath,
+                point: Vector2,
+            ) -> Option<Vector2> {
+                body_node(base, body).map(|b| base.to_local(b.to_global(point)))
+            }
+
+            fn draw_anchors(&self, base: TRef<'_, Node2D>) {
+                let props = &self.joint_props;
+                let a = Self::to_joint_local(base, &props.body_a, props.anchor_a);
+                let b = Self::to_joint_local(base, &props.body_b, props.anchor_b);
+                if let (Some(a), Some(b)) = (a, b) {
+                    base.draw_line(a, b, joint_color!(), 2., false);
+                }
+                a.into_iter()
+                    .chain(b)
+                    .for_each(|p| base.draw_circle(p, 4., joint_color!()));
+            }
+        }
+    };
+}
+
+macro_rules! proxy_properties_to_joint_props {
+    ($builder: ident) => {
+        $builder
+            .property("body_a")
+            .with_default(NodePath::default())
+            .with_ref_getter(|s, _| &s.joint_props.body_a)
+            .with_setter(|s, b, new_val| {
+                s.joint_props.body_a = new_val;
+                b.update();
+                s.detach();
+                s.attach(b);
+            })
+            .done();
+        $builder
+            .property("body_b")
+            .with_default(NodePath::default())
+            .with_ref_getter(|s, _| &s.joint_props.body_b)
+            .with_setter(|s, b, new_val| {
+                s.joint_props.body_b = new_val;
+                b.update();
+                s.detach();
+                s.attach(b);
+            })
+            .done();
+        $builder
+            .property("anchor_a")
+            .with_default(Vector2::ZERO)
+            .with_getter(|s, _| s.joint_props.anchor_a)
+            .with_setter(|s, b, new_val| {
+                s.joint_props.anchor_a = new_val;
+                b.update();
+                s.update_anchors();
+            })
+            .done();
+        $builder
+            .property("anchor_b")
+            .with_default(Vector2::ZERO)
+            .with_getter(|s, _| s.joint_props.anchor_b)
+            .with_setter(|s, b, new_val| {
+                s.joint_props.anchor_b = new_val;
+                b.update();
+                s.update_anchors();
+            })
+            .done();
+        $builder
+            .property("multibody")
+            .with_default(false)
+            .with_getter(|s, _| s.joint_props.multibody)
+            .with_setter(|s, b, new_val| {
+                s.joint_props.multibody = new_val;
+                s.detach();
+                s.attach(b);
+            })
+            .done();
+        $builder
+            .property("break_force")
+            .with_default(f32::INFINITY)
+            .with_getter(|s, _| s.joint_props.break_limits.force)
+            .with_setter(|s, _, new_val| {
+                s.joint_props.break_limits.force = new_val;
+                s.update_break_limits();
+            })
+            .done();
+        $builder
+            .property("break_torque")
+            .with_default(f32::INFINITY)
+            .with_getter(|s, _| s.joint_props.break_limits.torque)
+            .with_setter(|s, _, new_val| {
+                s.joint_props.break_limits.torque = new_val;
+                s.update_break_limits();
+            })
+            .done();
+    };
+}
+
+// Convenience macro for joints with a motorized axis, given by `MOTOR_AXIS`, `motor_length_scale` and `motor_force_scale`.
+macro_rules! impl_joint_motor {
+    ($t:ty) => {
+        impl $t {
+            fn update_motor(&self) {
+                handle_or_return!(self, handle);
+                let Some(ppm) = self.world_props.world_ppm else {return};
+                mut_world_or_return!(self, world);
+                let Some(joint) = world.joint_data_mut(handle) else {return};
+                self.motor_props.apply(
+                    joint,
+                    Self::MOTOR_AXIS,
+                    Self::motor_length_scale(ppm),
+                    Self::motor_force_scale(ppm),
+                );
+
+                // sleeping bodies would not notice the motor's new targets.
+                let Some((body_a, body_b)) = self.world_props.bodies else {return};
+                for body in [body_a, body_b] {
+                    if let Some(body) = world.rigid_body_set.get_mut(body) {
+                        body.wake_up(true);
+                    }
+                }
+            }
+
+            // position along the motorized axis, in the units of the motor's target position.
+            fn joint_coordinate(&self) -> Option<f32> {
+                let handle = self.world_props.handle?;
+                let (body_a, body_b) = self.world_props.bodies?;
+                let ppm = self.world_props.world_ppm?;
+                let world = self
+                    .world_props
+                    .world_ref
+                    .as_ref()
+                    .and_then(Weak::upgrade)?;
+                let world = world.borrow();
+
+                let joint = world.joint_data(handle)?;
+                let frame_a = world.rigid_body_set.get(body_a)?.position() * joint.local_frame1;
+                let frame_b = world.rigid_body_set.get(body_b)?.position() * joint.local_frame2;
+                let relative = frame_a.inv_mul(&frame_b);
+                Some(match Self::MOTOR_AXIS {
+                    r2d::JointAxis::AngX => relative.rotation.angle(),
+                    _ => relative.translation.x / Self::motor_length_scale(ppm),
+                })
+            }
+        }
+    };
+}
+
+macro_rules! proxy_properties_to_motor_props {
+    ($builder: ident) => {
+        $builder
+            .property("motor_enabled")
+            .with_default(false)
+            .with_getter(|s, _| s.motor_props.motor_enabled)
+            .with_setter(|s, _, new_val| {
+                s.motor_props.motor_enabled = new_val;
+                s.update_motor();
+            })
+            .done();
+        $builder
+            .property("motor_target_position")
+            .with_default(0.)
+            .with_getter(|s, _| s.motor_props.target_position)
+            .with_setter(|s, _, new_val| {
+                s.motor_props.target_position = new_val;
+                s.update_motor();
+            })
+            .done();
+        $builder
+            .property("motor_target_velocity")
+            .with_default(0.)
+            .with_getter(|s, _| s.motor_props.target_velocity)
+            .with_setter(|s, _, new_val| {
+                s.motor_props.target_velocity = new_val;
+                s.update_motor();
+            })
+            .done();
+        $builder
+            .property("motor_stiffness")
+            .with_default(0.)
+            .with_getter(|s, _| s.motor_props.stiffness)
+            .with_setter(|s, _, new_val| {
+                s.motor_props.stiffness = new_val;
+                s.update_motor();
+            })
+            .done();
+        $builder
+            .property("motor_damping")
+            .with_default(1.)
+            .with_getter(|s, _| s.motor_props.damping)
+            .with_setter(|s, _, new_val| {
+                s.motor_props.damping = new_val;
+                s.update_motor();
+            })
+            .done();
+        $builder
+            .property("motor_max_force")
+            .with_default(f32::INFINITY)
+            .with_getter(|s, _| s.motor_props.max_force)
+            .with_setter(|s, _, new_val| {
+                s.motor_props.max_force = new_val;
+                s.update_motor();
+            })
+            .done();
+        $builder
+            .property("limits_enabled")
+            .with_default(false)
+            .with_getter(|s, _| s.motor_props.limits_enabled)
+            .with_setter(|s, _, new_val| {
+                s.motor_props.limits_enabled = new_val;
+                s.update_motor();
+            })
+            .done();
+        $builder
+            .property("lower_limit")
+            .with_default(0.)
+            .with_getter(|s, _| s.motor_props.lower_limit)
+            .with_setter(|s, _, new_val| {
+                s.motor_props.lower_limit = new_val;
+                s.update_motor();
+            })
+            .done();
+        $builder
+            .property("upper_limit")
+            .with_default(0.)
+            .with_getter(|s, _| s.motor_props.upper_limit)
+            .with_setter(|s, _, new_val| {
+                s.motor_props.upper_limit = new_val;
+                s.update_motor();
+            })
+            .done();
+    };
+}
+
+macro_rules! register_emitted_signals {
+    ($builder:ident) => {
+        $builder
+            .signal("joint_broken")
+            .with_param("impulse", VariantType::F64)
+            .done();
+    };
+}
+
+// exports the methods common to all joints along with the class's own.
+// trailing idents name further methods drawing on top of the anchors.
+macro_rules! gen_joint_impl {
+    // motorized joints also export their coordinate along the motor axis.
+    ($t:ty, motor $(, $draw:ident)*; $($methods:tt)*) => {
+        gen_joint_impl!($t $(, $draw)*;
+            // 0 while not jointed.
+            #[method]
+            fn get_joint_coordinate(&self) -> f32 {
+                self.joint_coordinate().unwrap_or(0.)
+            }
+
+            $($methods)*
+        );
+    };
+    ($t:ty $(, $draw:ident)*; $($methods:tt)*) => {
+        #[methods]
+        impl $t {
+            $($methods)*
+
+            #[method]
+            fn _process(&mut self, #[base] base: TRef<'_, Node2D>, _delta: f32) {
+                // anchors follow their bodies around.
+                if should_draw!(base) {
+                    base.update();
+                }
+            }
+
+            #[method]
+            fn _physics_process(&mut self, #[base] base: TRef<'_, Node2D>, _delta: f32) {
+                not_in_editor!();
+                self.sync_with_world(base);
+            }
+
+            #[method]
+            pub fn _draw(&self, #[base] base: TRef<'_, Node2D>) {
+                if should_draw!(base) {
+                    self.draw_anchors(base);
+                    $(self.$draw(base);)*
+                }
+            }
+        }
+    };
+}
+
+// --------------------------------
+// Fixed
+
+#[derive(NativeClass)]
+#[inherit(Node2D)]
+#[register_with(Self::register_properties)]
+pub struct GodotRapier2DFixedJoint {
+    world_props: WorldProps,
+    joint_props: JointProps,
+}
+proxy_world_awareness_to_world_props!(GodotRapier2DFixedJoint);
+impl_joint_attachment!(GodotRapier2DFixedJoint);
+gen_joint_impl!(GodotRapier2DFixedJoint;
+    pub fn new(_base: &Node2D) -> Self {
+        Self {
+            world_props: WorldProps::default(),
+            joint_props: JointProps::default(),
+        }
+    }
+
+    // welds both bodies in the relative orientation they have when jointed.
+    fn build_joint(
+        &self,
+        body_a: &r2d::RigidBody,
+        body_b: &r2d::RigidBody,
+        ppm: PixelsPerMeter,
+    ) -> r2d::GenericJoint {
+        use rapier2d::prelude::*;
+        let (a, b) = (self.joint_props.anchor_a, self.joint_props.anchor_b);
+        let rotation = body_a.rotation().angle() - body_b.rotation().angle();
+        FixedJointBuilder::new()
+            .local_frame1(Isometry::translation(a.x / ppm.0, a.y / ppm.0))
+            .local_frame2(Isometry::new(vector![b.x / ppm.0, b.y / ppm.0], rotation))
+            .into()
+    }
+
+    fn register_properties(builder: &ClassBuilder<GodotRapier2DFixedJoint>) {
+        proxy_properties_to_joint_props!(builder);
+        register_emitted_signals!(builder);
+    }
+);
+
+// --------------------------------
+// Revolute
+
+#[derive(NativeClass)]
+#[inherit(Node2D)]
+#[register_with(Self::register_properties)]
+pub struct GodotRapier2DRevoluteJoint {
+    world_props: WorldProps,
+    joint_props: JointProps,
+    // angles in radians, torques in pixel units.
+    motor_props: MotorProps,
+}
+proxy_world_awareness_to_world_props!(GodotRapier2DRevoluteJoint);
+impl_joint_attachment!(GodotRapier2DRevoluteJoint);
+impl_joint_motor!(GodotRapier2DRevoluteJoint);
+impl GodotRapier2DRevoluteJoint {
+    const MOTOR_AXIS: r2d::JointAxis = r2d::JointAxis::AngX;
+
+    fn motor_length_scale(_ppm: PixelsPerMeter) -> f32 {
+        1.
+    }
+
+    // torques in pixel units scale with the square of the length.
+    fn motor_force_scale(ppm: PixelsPerMeter) -> f32 {
+        1. / (ppm.0 * ppm.0)
+    }
+}
+gen_joint_impl!(GodotRapier2DRevoluteJoint, motor;
+    pub fn new(_base: &Node2D) -> Self {
+        Self {
+            world_props: WorldProps::default(),
+            joint_props: JointProps::default(),
+            motor_props: MotorProps::default(),
+        }
+    }
+
+    // angles are measured relative to the orientation both bodies have when jointed.
+    fn build_joint(
+        &self,
+        body_a: &r2d::RigidBody,
+        body_b: &r2d::RigidBody,
+        ppm: PixelsPerMeter,
+    ) -> r2d::GenericJoint {
+        use rapier2d::prelude::*;
+        let (a, b) = (self.joint_props.anchor_a, self.joint_props.anchor_b);
+        let mut joint: GenericJoint = RevoluteJointBuilder::new()
+            .local_anchor1(point![a.x / ppm.0, a.y / ppm.0])
+            .local_anchor2(point![b.x / ppm.0, b.y / ppm.0])
+            .into();
+        joint.local_frame2.rotation =
+            Rotation::new(body_a.rotation().angle() - body_b.rotation().angle());
+        self.motor_props.apply(
+            &mut joint,
+            Self::MOTOR_AXIS,
+            Self::motor_length_scale(ppm),
+            Self::motor_force_scale(ppm),
+        );
+        joint
+    }
+
+    fn register_properties(builder: &ClassBuilder<GodotRapier2DRevoluteJoint>) {
+        proxy_properties_to_joint_props!(builder);
+        register_emitted_signals!(builder);
+        proxy_properties_to_motor_props!(builder);
+    }
+);
+
+// --------------------------------
+// Prismatic
+
+#[derive(NativeClass)]
+#[inherit(Node2D)]
+#[register_with(Self::register_properties)]
+pub struct GodotRapier2DPrismaticJoint {
+    world_props: WorldProps,
+    joint_props: JointProps,
+    // lengths and forces in pixel units.
+    motor_props: MotorProps,
+    // local to body a.
+    axis: Vector2,
+}
+proxy_world_awareness_to_world_props!(GodotRapier2DPrismaticJoint);
+impl_joint_attachment!(GodotRapier2DPrismaticJoint);
+impl_joint_motor!(GodotRapier2DPrismaticJoint);
+impl GodotRapier2DPrismaticJoint {
+    const MOTOR_AXIS: r2d::JointAxis = r2d::JointAxis::X;
+
+    fn motor_length_scale(ppm: PixelsPerMeter) -> f32 {
+        1. / ppm.0
+    }
+
+    fn motor_force_scale(ppm: PixelsPerMeter) -> f32 {
+        1. / ppm.0
+    }
+}
+gen_joint_impl!(GodotRapier2DPrismaticJoint, motor, draw_axis;
+    pub fn new(_base: &Node2D) -> Self {
+        Self {
+            world_props: WorldProps::default(),
+            joint_props: JointProps::default(),
+            motor_props: MotorProps::default(),
+            axis: Vector2::RIGHT,
+        }
+    }
+
+    // the axis is given local to body a, body b slides along it in their current relative orientation.
+    fn build_joint(
+        &self,
+        body_a: &r2d::RigidBody,
+        body_b: &r2d::RigidBody,
+        ppm: PixelsPerMeter,
+    ) -> r2d::GenericJoint {
+        use rapier2d::prelude::*;
+        let (a, b) = (self.joint_props.anchor_a, self.joint_props.anchor_b);
+        let axis_1 = UnitVector::try_new(vector![self.axis.x, self.axis.y], Real::EPSILON)
+            .unwrap_or_else(Vector::x_axis);
+        let axis_2 = body_b.rotation().inverse() * body_a.rotation() * axis_1;
+        let mut joint: GenericJoint = PrismaticJointBuilder::new(axis_1)
+            .local_axis2(axis_2)
+            .local_anchor1(point![a.x / ppm.0, a.y / ppm.0])
+            .local_anchor2(point![b.x / ppm.0, b.y / ppm.0])
+            .into();
+        self.motor_props.apply(
+            &mut joint,
+            Self::MOTOR_AXIS,
+            Self::motor_length_scale(ppm),
+            Self::motor_force_scale(ppm),
+        );
+        joint
+    }
+
+    fn register_properties(builder: &ClassBuilder<GodotRapier2DPrismaticJoint>) {
+        proxy_properties_to_joint_props!(builder);
+        register_emitted_signals!(builder);
+        proxy_properties_to_motor_props!(builder);
+        builder
+            .property("axis")
+            .with_default(Vector2::RIGHT)
+            .with_getter(|s, _| s.axis)
+            .with_setter(|s, b, new_val| {
+                s.axis = new_val;
+                b.update();
+                s.detach();
+                s.attach(b);
+            })
+            .done();
+    }
+
+    // the axis is drawn through the anchor on body a.
+    fn draw_axis(&self, base: TRef<'_, Node2D>) {
+        let direction = if self.axis == Vector2::ZERO {
+            Vector2::RIGHT
+        } else {
+            self.axis.normalized()
+        };
+        let offset = direction * AXIS_DRAW_LENGTH;
+        let anchor = self.joint_props.anchor_a;
+        let body = &self.joint_props.body_a;
+        let from = Self::to_joint_local(base, body, anchor - offset);
+        let to = Self::to_joint_local(base, body, anchor + offset);
+        if let (Some(from), Some(to)) = (from, to) {
+            base.draw_line(from, to, joint_color!(), 1., false);
+        }
+    }
+);
@@ -0,0 +1,311 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    f32::consts::{FRAC_PI_4, PI, TAU},
+    rc::{Rc, Weak},
+};
+
+use gdnative::api::Bone2D;
+use gdnative::export::hint::{FloatHint, RangeHint};
+use gdnative::prelude::*;
+use rapier2d::prelude::{self as r2d};
+
+use super::common::{not_in_editor, PixelsPerMeter, World2DAware, World2DState};
+
+// a bone of the target skeleton, simulated by a body of its own.
+struct RagdollBone {
+    node: Ref<Bone2D>,
+    name: String,
+    body: r2d::RigidBodyHandle,
+    // local pose the animation last gave the bone, in pixels and radians.
+    animated: Option<(Vector2, f64)>,
+    // local pose the bone was left in by blending, telling it apart from a newly animated one.
+    blended: Option<(Vector2, f64)>,
+}
+
+#[derive(Default)]
+struct WorldProps {
+    world_ref: Option<Weak<RefCell<World2DState>>>,
+    world_ppm: Option<PixelsPerMeter>,
+    // ordered such that parent bones precede their children.
+    bones: Vec<RagdollBone>,
+}
+
+// receive mutable world ref or abort current routine.
+macro_rules! mut_world_or_return {
+    ($s:ident,$i:ident) => {
+        let Some(world) = $s.world_props.world_ref.as_ref().and_then(Weak::upgrade) else {return};
+        let mut $i = world.borrow_mut();
+    };
+}
+
+// simulates the bones of a Skeleton2D as capsule bodies joined by limited revolute joints.
+#[derive(NativeClass)]
+#[inherit(Node2D)]
+#[register_with(Self::register_properties)]
+pub struct GodotRapier2DRagdoll {
+    world_props: WorldProps,
+    skeleton: NodePath,
+    // in pixels.
+    width: f32,
+    // in radians, either way from the rest angle towards the parent bone.
+    angle_limit: f32,
+    // 0 follows the animation, 1 the simulation.
+    blend: f32,
+    // per-bone overrides of `blend`, by bone name.
+    bone_blends: HashMap<String, f32>,
+}
+
+// bones are only built once the world is simulated.
+impl World2DAware<TRef<'_, Node2D>> for GodotRapier2DRagdoll {
+    type Handle = ();
+
+    fn add_to_world(
+        &mut self,
+        _base: TRef<'_, Node2D>,
+        world_ref: &super::common::World2DRef,
+        _handle: Self::Handle,
+        ppm: PixelsPerMeter,
+    ) {
+        self.world_props.world_ref = Some(Rc::downgrade(world_ref));
+        self.world_props.world_ppm = Some(ppm);
+    }
+
+    fn remove_from_world(&mut self, _base: TRef<'_, Node2D>) {
+        self.remove_bones();
+
+        self.world_props.world_ref = None;
+        self.world_props.world_ppm = None;
+    }
+}
+
+// bones below `node` in tree order, so that parents precede their children.
+fn collect_bones(node: TRef<'_, Node>, bones: &mut Vec<Ref<Bone2D>>) {
+    node.get_children().iter().for_each(|c| {
+        let Ok(child) = c.try_to::<Ref<Node>>() else {return};
+        let child = unsafe { child.assume_safe() };
+        if let Some(bone) = child.cast::<Bone2D>() {
+            bones.push(bone.claim());
+        }
+        collect_bones(child, bones);
+    })
+}
+
+fn lerp_angle(from: f32, to: f32, weight: f32) -> f32 {
+    let difference = (to - from + PI).rem_euclid(TAU) - PI;
+    from + difference * weight
+}
+
+impl GodotRapier2DRagdoll {
+    fn build_bones(&mut self, base: TRef<'_, Node2D>) {
+        use rapier2d::prelude::*;
+        if !self.world_props.bones.is_empty() {
+            return;
+        }
+        let Some(ppm) = self.world_props.world_ppm else {return};
+        let Some(skeleton) = base.get_node_or_null(self.skeleton.new_ref()) else {return};
+        let mut bone_nodes = vec![];
+        collect_bones(unsafe { skeleton.assume_safe() }, &mut bone_nodes);
+        mut_world_or_return!(self, world);
+
+        let radius = self.width / 2. / ppm.0;
+        let mut bodies = HashMap::new();
+        for node in bone_nodes {
+            let bone = unsafe { node.assume_safe() };
+            let origin = point![
+                bone.global_position().x / ppm.0,
+                bone.global_position().y / ppm.0
+            ];
+            let rotation = bone.global_rotation() as f32;
+            let half_length = bone.default_length() as f32 / 2. / ppm.0;
+
+            let body = world.rigid_body_set.insert(
+                RigidBodyBuilder::dynamic()
+                    .translation(origin.coords)
+                    .rotation(rotation)
+                    .build(),
+            );
+            // bones extend along their local x axis.
+            world.insert_collider_with_parent(
+                ColliderBuilder::capsule_x((half_length - radius).max(0.), radius)
+                    .translation(vector![half_length, 0.])
+                    .build(),
+                body,
+            );
+
+            let parent = bone
+                .get_parent()
+                .and_then(|p| unsafe { p.assume_safe() }.cast::<Bone2D>())
+                .and_then(|p| bodies.get(&p.get_instance_id()).copied());
+            if let Some(parent) = parent {
+                // jointed at the bone's origin, limited around its current angle towards the parent.
+                let parent_position = world.rigid_body_set[parent].position();
+                let mut joint: GenericJoint = RevoluteJointBuilder::new()
+                    .local_anchor1(parent_position.inverse_transform_point(&origin))
+                    .local_anchor2(point![0., 0.])
+                    .limits([-self.angle_limit, self.angle_limit])
+                    .contacts_enabled(false)
+                    .into();
+                joint.local_frame2.rotation =
+                    Rotation::new(parent_position.rotation.angle() - rotation);
+                world.impulse_joints.insert(parent, body, joint, true);
+            }
+
+            bodies.insert(bone.get_instance_id(), body);
+            self.world_props.bones.push(RagdollBone {
+                node,
+                name: bone.name().to_string(),
+                body,
+                animated: None,
+                blended: None,
+            });
+        }
+    }
+
+    fn remove_bones(&mut self) {
+        let bones = std::mem::take(&mut self.world_props.bones);
+        mut_world_or_return!(self, world);
+        for bone in bones {
+            world.remove_rigid_body_and_colliders(bone.body);
+        }
+    }
+
+    // writes simulated poses onto the bones, blended with their animated ones.
+    fn sync_bones(&mut self) {
+        use rapier2d::prelude::*;
+        let Some(ppm) = self.world_props.world_ppm else {return};
+        mut_world_or_return!(self, world);
+
+        // bones still in their blended pose were not animated since, and blend from their last animated one.
+        for bone in &mut self.world_props.bones {
+            let Some(node) = (unsafe { bone.node.assume_safe_if_sane() }) else {continue};
+            let local = (node.position(), node.rotation());
+            match (bone.blended, bone.animated) {
+                (Some(blended), Some((position, rotation))) if blended == local => {
+                    node.set_position(position);
+                    node.set_rotation(rotation);
+                }
+                _ => bone.animated = Some(local),
+            }
+        }
+
+        // children move along with their parents, so all animated poses are read before writing any.
+        let animated: Vec<_> = self
+            .world_props
+            .bones
+            .iter()
+            .map(|b| {
+                let blend = self.bone_blend(&b.name);
+                unsafe { b.node.assume_safe_if_sane() }
+                    .map(|n| (n, n.global_position(), n.global_rotation() as f32, blend))
+            })
+            .collect();
+
+        for (bone, animated) in self.world_props.bones.iter_mut().zip(animated) {
+            let Some((node, position, rotation, blend)) = animated else {continue};
+            let Some(body) = world.rigid_body_set.get_mut(bone.body) else {continue};
+            bone.blended = None;
+
+            if blend <= 0. {
+                // fully animated bones drag their body along.
+                body.set_body_type(RigidBodyType::KinematicPositionBased);
+                body.set_next_kinematic_position(Isometry::new(
+                    vector![position.x / ppm.0, position.y / ppm.0],
+                    rotation,
+                ));
+                continue;
+            }
+
+            body.set_body_type(RigidBodyType::Dynamic);
+            let simulated = body.position();
+            let simulated_position = Vector2 {
+                x: simulated.translation.x * ppm.0,
+                y: simulated.translation.y * ppm.0,
+            };
+            node.set_global_position(position.linear_interpolate(simulated_position, blend));
+            node.set_global_rotation(
+                lerp_angle(rotation, simulated.rotation.angle(), blend) as f64,
+            );
+            bone.blended = Some((node.position(), node.rotation()));
+        }
+    }
+
+    fn bone_blend(&self, bone: &str) -> f32 {
+        self.bone_blends.get(bone).copied().unwrap_or(self.blend)
+    }
+}
+
+#[methods]
+impl GodotRapier2DRagdoll {
+    pub fn new(_base: &Node2D) -> Self {
+        Self {
+            world_props: WorldProps::default(),
+            skeleton: NodePath::default(),
+            width: 8.,
+            angle_limit: FRAC_PI_4,
+            blend: 1.,
+            bone_blends: HashMap::new(),
+        }
+    }
+
+    fn register_properties(builder: &ClassBuilder<GodotRapier2DRagdoll>) {
+        builder
+            .property("skeleton")
+            .with_default(NodePath::default())
+            .with_ref_getter(|s, _| &s.skeleton)
+            .with_setter(|s, _, new_val| {
+                s.skeleton = new_val;
+                s.remove_bones();
+            })
+            .done();
+        builder
+            .property("width")
+            .with_default(8.)
+            .with_getter(|s, _| s.width)
+            .with_setter(|s, _, new_val| {
+                s.width = new_val;
+                s.remove_bones();
+            })
+            .done();
+        builder
+            .property("angle_limit")
+            .with_default(FRAC_PI_4)
+            .with_hint(FloatHint::Range(RangeHint::new(0.0, PI).with_step(0.01)))
+            .with_getter(|s, _| s.angle_limit)
+            .with_setter(|s, _, new_val| {
+                s.angle_limit = new_val;
+                s.remove_bones();
+            })
+            .done();
+        builder
+            .property("blend")
+            .with_default(1.)
+            .with_hint(FloatHint::Range(RangeHint::new(0.0, 1.0).with_step(0.01)))
+            .with_getter(|s, _| s.blend)
+            .with_setter(|s, _, new_val| s.blend = new_val)
+            .done();
+    }
+
+    #[method]
+    fn set_bone_blend(&mut self, bone: String, blend: f32) {
+        self.bone_blends.insert(bone, blend);
+    }
+
+    #[method]
+    fn get_bone_blend(&self, bone: String) -> f32 {
+        self.bone_blend(&bone)
+    }
+
+    // reverts the bone to the ragdoll's overall blend.
+    #[method]
+    fn clear_bone_blend(&mut self, bone: String) {
+        self.bone_blends.remove(&bone);
+    }
+
+    #[method]
+    fn _physics_process(&mut self, #[base] base: TRef<'_, Node2D>, _delta: f32) {
+        not_in_editor!();
+        self.build_bones(base);
+        self.sync_bones();
+    }
+}
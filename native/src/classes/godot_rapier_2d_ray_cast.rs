@@ -0,0 +1,213 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use gdnative::export::hint::IntHint;
+use gdnative::prelude::*;
+use rapier2d::prelude::{self as r2d};
+
+use super::common::{
+    node_from_instance_id, not_in_editor, should_draw, PixelsPerMeter, World2DAware, World2DState,
+};
+
+macro_rules! ray_color {
+    () => {
+        Color::from_html("88FF0000").unwrap()
+    };
+}
+macro_rules! ray_hit_color {
+    () => {
+        Color::from_html("88FF8800").unwrap()
+    };
+}
+
+#[derive(Default)]
+struct WorldProps {
+    world_ref: Option<Weak<RefCell<World2DState>>>,
+    world_ppm: Option<PixelsPerMeter>,
+    // body the ray is attached to, if any.
+    parent: Option<r2d::RigidBodyHandle>,
+}
+
+// closest hit of the last cast, in global pixels.
+struct RayHit {
+    // instance id, as the node may be freed before the hit is read.
+    collider: Option<i64>,
+    point: Vector2,
+    normal: Vector2,
+}
+
+// persistent ray, recast on every physics frame once the world has been stepped.
+#[derive(NativeClass)]
+#[inherit(Node2D)]
+#[register_with(Self::register_properties)]
+pub struct GodotRapier2DRayCast {
+    world_props: WorldProps,
+    // in local pixels.
+    cast_to: Vector2,
+    collision_mask: u32,
+    exclude_parent: bool,
+    hit: Option<RayHit>,
+}
+
+// the handle is the one of the body the ray is attached to, if any.
+impl World2DAware<TRef<'_, Node2D>> for GodotRapier2DRayCast {
+    type Handle = Option<r2d::RigidBodyHandle>;
+
+    fn add_to_world(
+        &mut self,
+        base: TRef<'_, Node2D>,
+        world_ref: &super::common::World2DRef,
+        handle: Self::Handle,
+        ppm: PixelsPerMeter,
+    ) {
+        self.world_props.world_ref = Some(Rc::downgrade(world_ref));
+        self.world_props.world_ppm = Some(ppm);
+        self.world_props.parent = handle;
+        base.update()
+    }
+
+    fn remove_from_world(&mut self, base: TRef<'_, Node2D>) {
+        self.world_props.world_ref = None;
+        self.world_props.world_ppm = None;
+        self.world_props.parent = None;
+        self.hit = None;
+        base.update();
+    }
+}
+
+impl GodotRapier2DRayCast {
+    fn recast(&mut self, base: TRef<'_, Node2D>) {
+        use rapier2d::prelude::*;
+        let Some(ppm) = self.world_props.world_ppm else {return};
+        let Some(world) = self.world_props.world_ref.as_ref().and_then(Weak::upgrade) else {return};
+        let world = world.borrow();
+
+        let from = base.global_position();
+        let to = base.to_global(self.cast_to);
+        let ray = Ray::new(
+            point![from.x / ppm.0, from.y / ppm.0],
+            vector![(to.x - from.x) / ppm.0, (to.y - from.y) / ppm.0],
+        );
+        let filter = QueryFilter::new().groups(InteractionGroups::new(
+            Group::ALL,
+            Group::from_bits_truncate(self.collision_mask),
+        ));
+        let filter = match self.world_props.parent {
+            Some(parent) if self.exclude_parent => filter.exclude_rigid_body(parent),
+            _ => filter,
+        };
+
+        self.hit = world
+            .query_pipeline
+            .cast_ray_and_get_normal(
+                &world.rigid_body_set,
+                &world.collider_set,
+                &ray,
+                1.,
+                true,
+                filter,
+            )
+            .map(|(handle, hit)| {
+                let point = ray.point_at(hit.toi);
+                RayHit {
+                    collider: world.nodes.collider_instance_id(handle),
+                    point: Vector2 {
+                        x: point.x * ppm.0,
+                        y: point.y * ppm.0,
+                    },
+                    normal: Vector2 {
+                        x: hit.normal.x,
+                        y: hit.normal.y,
+                    },
+                }
+            });
+    }
+}
+
+#[methods]
+impl GodotRapier2DRayCast {
+    pub fn new(_base: &Node2D) -> Self {
+        Self {
+            world_props: WorldProps::default(),
+            cast_to: Vector2 { x: 0., y: 50. },
+            collision_mask: 1,
+            exclude_parent: true,
+            hit: None,
+        }
+    }
+
+    fn register_properties(builder: &ClassBuilder<GodotRapier2DRayCast>) {
+        builder
+            .property("cast_to")
+            .with_default(Vector2 { x: 0., y: 50. })
+            .with_getter(|s, _| s.cast_to)
+            .with_setter(|s, b, new_val| {
+                s.cast_to = new_val;
+                b.update();
+            })
+            .done();
+        builder
+            .property("collision_mask")
+            .with_default(1)
+            .with_hint(IntHint::Layers2DPhysics)
+            .with_getter(|s, _| s.collision_mask)
+            .with_setter(|s, _, new_val| s.collision_mask = new_val)
+            .done();
+        builder
+            .property("exclude_parent")
+            .with_default(true)
+            .with_getter(|s, _| s.exclude_parent)
+            .with_setter(|s, _, new_val| s.exclude_parent = new_val)
+            .done();
+    }
+
+    #[method]
+    fn is_colliding(&self) -> bool {
+        self.hit.is_some()
+    }
+
+    #[method]
+    fn get_collider(&self) -> Option<Ref<Node2D>> {
+        self.hit
+            .as_ref()
+            .and_then(|h| h.collider)
+            .and_then(node_from_instance_id)
+    }
+
+    // in global pixels.
+    #[method]
+    fn get_collision_point(&self) -> Vector2 {
+        self.hit.as_ref().map_or(Vector2::ZERO, |h| h.point)
+    }
+
+    #[method]
+    fn get_collision_normal(&self) -> Vector2 {
+        self.hit.as_ref().map_or(Vector2::ZERO, |h| h.normal)
+    }
+
+    #[method]
+    fn _physics_process(&mut self, #[base] base: TRef<'_, Node2D>, _delta: f32) {
+        not_in_editor!();
+        // parents process first, so the world has already been stepped this frame.
+        self.recast(base);
+        if should_draw!(base) {
+            base.update();
+        }
+    }
+
+    #[method]
+    pub fn _draw(&self, #[base] base: TRef<'_, Node2D>) {
+        if should_draw!(base) {
+            match &self.hit {
+                Some(hit) => {
+                    let point = base.to_local(hit.point);
+                    base.draw_line(Vector2::ZERO, point, ray_hit_color!(), 2., true);
+                    base.draw_circle(point, 3., ray_hit_color!());
+                }
+                None => base.draw_line(Vector2::ZERO, self.cast_to, ray_color!(), 2., true),
+            }
+        }
+    }
+}
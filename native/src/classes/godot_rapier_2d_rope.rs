@@ -0,0 +1,322 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use gdnative::export::hint::{IntHint, RangeHint};
+use gdnative::prelude::*;
+use rapier2d::prelude::{self as r2d};
+
+use super::common::{not_in_editor, PixelsPerMeter, World2DAware, World2DState};
+use super::godot_rapier_2d_joint::body_node;
+
+#[derive(Default)]
+struct WorldProps {
+    world_ref: Option<Weak<RefCell<World2DState>>>,
+    world_ppm: Option<PixelsPerMeter>,
+    // one body per rope segment, ordered from start to end.
+    segments: Vec<r2d::RigidBodyHandle>,
+    // in meters, as laid out when the segments were built.
+    segment_half_length: r2d::Real,
+}
+
+// receive mutable world ref or abort current routine.
+macro_rules! mut_world_or_return {
+    ($s:ident,$i:ident) => {
+        let Some(world) = $s.world_props.world_ref.as_ref().and_then(Weak::upgrade) else {return};
+        let mut $i = world.borrow_mut();
+    };
+}
+
+// point `distance` pixels along a polyline, or its last point past the end.
+fn point_along(path: &[Vector2], distance: f32) -> Vector2 {
+    let mut remaining = distance;
+    for leg in path.windows(2) {
+        let length = leg[0].distance_to(leg[1]);
+        if remaining <= length {
+            return if length > 0. {
+                leg[0] + (leg[1] - leg[0]) * (remaining / length)
+            } else {
+                leg[0]
+            };
+        }
+        remaining -= length;
+    }
+    path[path.len() - 1]
+}
+
+// chain of capsule bodies joined by revolute joints, hung between two optional endpoint bodies.
+// lengths are in pixels.
+#[derive(NativeClass)]
+#[inherit(Node2D)]
+#[register_with(Self::register_properties)]
+pub struct GodotRapier2DRope {
+    world_props: WorldProps,
+    start: NodePath,
+    end: NodePath,
+    segment_count: i64,
+    // kept between two endpoints too, the rope sags if longer than their distance and is pulled along if shorter.
+    segment_length: f32,
+    width: f32,
+    color: Color,
+}
+
+// segments are only built once both endpoints are part of the world.
+impl World2DAware<TRef<'_, Node2D>> for GodotRapier2DRope {
+    type Handle = ();
+
+    fn add_to_world(
+        &mut self,
+        base: TRef<'_, Node2D>,
+        world_ref: &super::common::World2DRef,
+        _handle: Self::Handle,
+        ppm: PixelsPerMeter,
+    ) {
+        self.world_props.world_ref = Some(Rc::downgrade(world_ref));
+        self.world_props.world_ppm = Some(ppm);
+        base.update()
+    }
+
+    fn remove_from_world(&mut self, base: TRef<'_, Node2D>) {
+        self.remove_segments();
+
+        self.world_props.world_ref = None;
+        self.world_props.world_ppm = None;
+        base.update();
+    }
+}
+
+impl GodotRapier2DRope {
+    // global positions in pixels of the segment ends, from the start of the rope to its end.
+    fn layout(&self, base: TRef<'_, Node2D>) -> Vec<Vector2> {
+        let from = body_node(base, &self.start)
+            .map(|n| n.global_position())
+            .unwrap_or_else(|| base.global_position());
+        let to = body_node(base, &self.end).map(|n| n.global_position());
+        let rope_length = self.segment_length * self.segment_count as f32;
+        let path = match to {
+            Some(to) if to != from => {
+                let direction = (to - from).normalized();
+                let distance = from.distance_to(to);
+                if distance >= rope_length {
+                    // the end joint pulls a rope too short to reach it.
+                    vec![from, from + direction * rope_length]
+                } else {
+                    // the slack hangs down between the endpoints.
+                    let normal = Vector2 {
+                        x: -direction.y,
+                        y: direction.x,
+                    };
+                    let normal = if normal.y < 0. { -normal } else { normal };
+                    let sag = ((rope_length / 2.).powi(2) - (distance / 2.).powi(2)).sqrt();
+                    vec![from, (from + to) / 2. + normal * sag, to]
+                }
+            }
+            // a loose end hangs straight down.
+            _ => vec![from, from + Vector2::DOWN * rope_length],
+        };
+        (0..=self.segment_count)
+            .map(|i| point_along(&path, self.segment_length * i as f32))
+            .collect()
+    }
+
+    fn build_segments(&mut self, base: TRef<'_, Node2D>) {
+        use rapier2d::prelude::*;
+        if !self.world_props.segments.is_empty() {
+            return;
+        }
+        let Some(ppm) = self.world_props.world_ppm else {return};
+        let ends = self.layout(base);
+        mut_world_or_return!(self, world);
+
+        // unassigned endpoints leave the rope loose, assigned ones must be bodies of the world.
+        let endpoint = |path: &NodePath| {
+            if path.is_empty() {
+                return Some(None);
+            }
+            body_node(base, path)
+                .and_then(|n| world.nodes.body_handle(n))
+                .map(Some)
+        };
+        let Some(start) = endpoint(&self.start) else {return};
+        let Some(end) = endpoint(&self.end) else {return};
+
+        let half_length = self.segment_length / 2. / ppm.0;
+        let radius = self.width / 2. / ppm.0;
+        self.world_props.segment_half_length = half_length;
+        let mut previous = start.map(|h| (h, point![0., 0.]));
+        for pair in ends.windows(2) {
+            let center = (pair[0] + pair[1]) / 2.;
+            let segment = world.rigid_body_set.insert(
+                RigidBodyBuilder::dynamic()
+                    .translation(vector![center.x / ppm.0, center.y / ppm.0])
+                    .rotation((pair[1] - pair[0]).angle())
+                    .build(),
+            );
+            world.insert_collider_with_parent(
+                ColliderBuilder::capsule_x((half_length - radius).max(0.), radius).build(),
+                segment,
+            );
+            if let Some((previous, anchor)) = previous {
+                let joint = RevoluteJointBuilder::new()
+                    .local_anchor1(anchor)
+                    .local_anchor2(point![-half_length, 0.])
+                    .contacts_enabled(false);
+                world.impulse_joints.insert(previous, segment, joint, true);
+            }
+            previous = Some((segment, point![half_length, 0.]));
+            self.world_props.segments.push(segment);
+        }
+        if let (Some((previous, anchor)), Some(end)) = (previous, end) {
+            let joint = RevoluteJointBuilder::new()
+                .local_anchor1(anchor)
+                .local_anchor2(point![0., 0.])
+                .contacts_enabled(false);
+            world.impulse_joints.insert(previous, end, joint, true);
+        }
+    }
+
+    fn remove_segments(&mut self) {
+        let segments = std::mem::take(&mut self.world_props.segments);
+        mut_world_or_return!(self, world);
+        for handle in segments {
+            world.remove_rigid_body_and_colliders(handle);
+        }
+    }
+
+    // rope outline in global pixels, following the simulated segments.
+    fn segment_points(&self) -> Option<Vec<Vector2>> {
+        use rapier2d::prelude::*;
+        let world = self
+            .world_props
+            .world_ref
+            .as_ref()
+            .and_then(Weak::upgrade)?;
+        let ppm = self.world_props.world_ppm?;
+        let world = world.borrow();
+
+        let half_length = self.world_props.segment_half_length;
+        let segment_end = |handle: &r2d::RigidBodyHandle, x: f32| {
+            let p = world.rigid_body_set.get(*handle)?.position() * point![x, 0.];
+            Some(Vector2 {
+                x: p.x * ppm.0,
+                y: p.y * ppm.0,
+            })
+        };
+        let segments = &self.world_props.segments;
+        let first = segment_end(segments.first()?, -half_length);
+        Some(
+            first
+                .into_iter()
+                .chain(segments.iter().filter_map(|h| segment_end(h, half_length)))
+                .collect(),
+        )
+    }
+
+    // rebuilds the segments with the current properties on the next physics frame.
+    fn invalidate(&mut self, base: TRef<'_, Node2D>) {
+        self.remove_segments();
+        base.update();
+    }
+}
+
+#[methods]
+impl GodotRapier2DRope {
+    pub fn new(_base: &Node2D) -> Self {
+        Self {
+            world_props: WorldProps::default(),
+            start: NodePath::default(),
+            end: NodePath::default(),
+            segment_count: 8,
+            segment_length: 16.,
+            width: 4.,
+            color: Color::from_rgb(1., 1., 1.),
+        }
+    }
+
+    fn register_properties(builder: &ClassBuilder<GodotRapier2DRope>) {
+        builder
+            .property("start")
+            .with_default(NodePath::default())
+            .with_ref_getter(|s, _| &s.start)
+            .with_setter(|s, b, new_val| {
+                s.start = new_val;
+                s.invalidate(b);
+            })
+            .done();
+        builder
+            .property("end")
+            .with_default(NodePath::default())
+            .with_ref_getter(|s, _| &s.end)
+            .with_setter(|s, b, new_val| {
+                s.end = new_val;
+                s.invalidate(b);
+            })
+            .done();
+        builder
+            .property("segment_count")
+            .with_default(8)
+            .with_hint(IntHint::Range(RangeHint::new(1, 64).or_greater()))
+            .with_getter(|s, _| s.segment_count)
+            .with_setter(|s, b, new_val| {
+                s.segment_count = new_val.max(1);
+                s.invalidate(b);
+            })
+            .done();
+        builder
+            .property("segment_length")
+            .with_default(16.)
+            .with_getter(|s, _| s.segment_length)
+            .with_setter(|s, b, new_val| {
+                s.segment_length = new_val;
+                s.invalidate(b);
+            })
+            .done();
+        builder
+            .property("width")
+            .with_default(4.)
+            .with_getter(|s, _| s.width)
+            .with_setter(|s, b, new_val| {
+                s.width = new_val;
+                s.invalidate(b);
+            })
+            .done();
+        builder
+            .property("color")
+            .with_default(Color::from_rgb(1., 1., 1.))
+            .with_getter(|s, _| s.color)
+            .with_setter(|s, b, new_val| {
+                s.color = new_val;
+                b.update();
+            })
+            .done();
+    }
+
+    #[method]
+    fn _process(&mut self, #[base] base: TRef<'_, Node2D>, _delta: f32) {
+        // the rope follows its segments, or its endpoints while in the editor.
+        base.update();
+    }
+
+    #[method]
+    fn _physics_process(&mut self, #[base] base: TRef<'_, Node2D>, _delta: f32) {
+        not_in_editor!();
+        self.build_segments(base);
+    }
+
+    #[method]
+    pub fn _draw(&self, #[base] base: TRef<'_, Node2D>) {
+        let points = self
+            .segment_points()
+            .unwrap_or_else(|| self.layout(base))
+            .into_iter()
+            .map(|p| base.to_local(p))
+            .collect();
+        base.draw_polyline(
+            Vector2Array::from_vec(points),
+            self.color,
+            self.width as f64,
+            true,
+        );
+    }
+}
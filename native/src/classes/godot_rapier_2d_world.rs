@@ -1,15 +1,26 @@
 use std::cell::RefCell;
+use std::collections::HashSet;
 use std::rc::Rc;
 
+use gdnative::api::{
+    CapsuleShape2D, CircleShape2D, ConvexPolygonShape2D, RectangleShape2D, SegmentShape2D, Shape2D,
+};
 use gdnative::export::hint::{FloatHint, RangeHint};
 use gdnative::prelude::*;
+use rapier2d::prelude::{self as r2d};
 
 use crate::classes::godot_rapier_2d_body::{
-    GodotRapier2DDynamicBody, GodotRapier2DFixedBody, GodotRapier2DKinematicPositionBasedBody,
-    GodotRapier2DKinematicVelocityBasedBody,
+    GodotRapier2DCharacterBody, GodotRapier2DDynamicBody, GodotRapier2DFixedBody,
+    GodotRapier2DKinematicPositionBasedBody, GodotRapier2DKinematicVelocityBasedBody,
+};
+use crate::classes::godot_rapier_2d_joint::{
+    GodotRapier2DFixedJoint, GodotRapier2DPrismaticJoint, GodotRapier2DRevoluteJoint,
 };
+use crate::classes::godot_rapier_2d_ragdoll::GodotRapier2DRagdoll;
+use crate::classes::godot_rapier_2d_ray_cast::GodotRapier2DRayCast;
+use crate::classes::godot_rapier_2d_rope::GodotRapier2DRope;
 
-use super::common::{not_in_editor, PixelsPerMeter, World2DAware, World2DState};
+use super::common::{not_in_editor, PendingSignal, PixelsPerMeter, World2DAware, World2DState};
 
 const G: f32 = 9.81;
 
@@ -24,7 +35,82 @@ pub struct GodotRapier2DWorld {
     gravity: Vector2,
 }
 
-impl GodotRapier2DWorld {}
+// godot shape resource as a rapier shape, `None` for kinds of shapes without counterpart.
+fn shared_shape(shape: &Ref<Shape2D>, ppm: PixelsPerMeter) -> Option<r2d::SharedShape> {
+    use rapier2d::prelude::*;
+    let shape = unsafe { shape.assume_safe() };
+    if let Some(s) = shape.cast::<CircleShape2D>() {
+        Some(SharedShape::ball(s.radius() as f32 / ppm.0))
+    } else if let Some(s) = shape.cast::<RectangleShape2D>() {
+        let extents = s.extents();
+        Some(SharedShape::cuboid(extents.x / ppm.0, extents.y / ppm.0))
+    } else if let Some(s) = shape.cast::<CapsuleShape2D>() {
+        // the height of godot 3 capsules leaves out their rounded ends.
+        Some(SharedShape::capsule_y(
+            s.height() as f32 / 2. / ppm.0,
+            s.radius() as f32 / ppm.0,
+        ))
+    } else if let Some(s) = shape.cast::<SegmentShape2D>() {
+        let (a, b) = (s.a(), s.b());
+        Some(SharedShape::segment(
+            point![a.x / ppm.0, a.y / ppm.0],
+            point![b.x / ppm.0, b.y / ppm.0],
+        ))
+    } else if let Some(s) = shape.cast::<ConvexPolygonShape2D>() {
+        let points: Vec<_> = s
+            .points()
+            .read()
+            .iter()
+            .map(|p| point![p.x / ppm.0, p.y / ppm.0])
+            .collect();
+        SharedShape::convex_hull(&points)
+    } else {
+        None
+    }
+}
+
+// placement of a shape in meters, scale is not supported.
+fn shape_position(transform: Transform2D, ppm: PixelsPerMeter) -> r2d::Isometry<r2d::Real> {
+    use rapier2d::prelude::*;
+    Isometry::new(
+        vector![transform.origin.x / ppm.0, transform.origin.y / ppm.0],
+        transform.rotation(),
+    )
+}
+
+impl GodotRapier2DWorld {
+    // runs `f` on the world with a query filter passing colliders in `mask`,
+    // except those of the `exclude` colliders and bodies.
+    fn with_query_filter<R>(
+        &self,
+        mask: Option<u32>,
+        exclude: Option<VariantArray>,
+        f: impl FnOnce(&World2DState, r2d::QueryFilter) -> R,
+    ) -> R {
+        let exclude: HashSet<i64> = exclude
+            .iter()
+            .flat_map(|a| a.iter())
+            .filter_map(|v| v.try_to::<Ref<Object>>().ok())
+            .filter_map(|o| unsafe { o.assume_safe_if_sane() })
+            .map(|o| o.get_instance_id())
+            .collect();
+        let groups = r2d::InteractionGroups::new(
+            r2d::Group::ALL,
+            r2d::Group::from_bits_truncate(mask.unwrap_or(u32::MAX)),
+        );
+
+        let world = self.rapier_world.borrow();
+        let predicate = |handle, collider: &r2d::Collider| {
+            !world
+                .nodes
+                .collider_owned_by(handle, collider.parent(), &exclude)
+        };
+        f(
+            &world,
+            r2d::QueryFilter::new().groups(groups).predicate(&predicate),
+        )
+    }
+}
 
 #[methods]
 impl GodotRapier2DWorld {
@@ -71,7 +157,278 @@ impl GodotRapier2DWorld {
     #[method]
     fn _physics_process(&mut self, #[base] _base: TRef<'_, Node2D>, _delta: f64) {
         not_in_editor!();
-        self.rapier_world.borrow_mut().tick();
+        let signals = self.rapier_world.borrow_mut().tick(self.pixels_per_meter);
+        // emitted only after releasing the world, so handlers may mutate it.
+        signals.into_iter().for_each(PendingSignal::emit);
+    }
+
+    // nearest collider hit on the way from `from` to `to`, or an empty dictionary when there is none.
+    #[method]
+    fn cast_ray(
+        &self,
+        from: Vector2,
+        to: Vector2,
+        #[opt] mask: Option<u32>,
+        #[opt] exclude: Option<VariantArray>,
+    ) -> Dictionary {
+        use rapier2d::prelude::*;
+        let ppm = self.pixels_per_meter;
+        let ray = Ray::new(
+            point![from.x / ppm.0, from.y / ppm.0],
+            vector![(to.x - from.x) / ppm.0, (to.y - from.y) / ppm.0],
+        );
+
+        let info = Dictionary::new();
+        self.with_query_filter(mask, exclude, |world, filter| {
+            let Some((handle, hit)) = world.query_pipeline.cast_ray_and_get_normal(
+                &world.rigid_body_set,
+                &world.collider_set,
+                &ray,
+                1.,
+                true,
+                filter,
+            ) else {return};
+
+            let point = ray.point_at(hit.toi);
+            info.insert("collider", world.nodes.collider_node(handle));
+            info.insert(
+                "body",
+                world
+                    .collider_set
+                    .get(handle)
+                    .and_then(Collider::parent)
+                    .and_then(|b| world.nodes.body_node(b)),
+            );
+            info.insert(
+                "position",
+                Vector2 {
+                    x: point.x * ppm.0,
+                    y: point.y * ppm.0,
+                },
+            );
+            info.insert(
+                "normal",
+                Vector2 {
+                    x: hit.normal.x,
+                    y: hit.normal.y,
+                },
+            );
+            info.insert("fraction", hit.toi);
+        });
+        info.into_shared()
+    }
+
+    // colliders containing `point`.
+    #[method]
+    fn intersect_point(
+        &self,
+        point: Vector2,
+        #[opt] mask: Option<u32>,
+        #[opt] exclude: Option<VariantArray>,
+    ) -> VariantArray {
+        use rapier2d::prelude::*;
+        let ppm = self.pixels_per_meter;
+        let point = point![point.x / ppm.0, point.y / ppm.0];
+
+        let colliders = VariantArray::new();
+        self.with_query_filter(mask, exclude, |world, filter| {
+            world.query_pipeline.intersections_with_point(
+                &world.rigid_body_set,
+                &world.collider_set,
+                &point,
+                filter,
+                |handle| {
+                    colliders.push(world.nodes.collider_node(handle));
+                    true
+                },
+            )
+        });
+        colliders.into_shared()
+    }
+
+    // colliders whose bounding boxes overlap `rect`.
+    #[method]
+    fn intersect_aabb(
+        &self,
+        rect: Rect2,
+        #[opt] mask: Option<u32>,
+        #[opt] exclude: Option<VariantArray>,
+    ) -> VariantArray {
+        use rapier2d::prelude::*;
+        let ppm = self.pixels_per_meter;
+        // an inverted aabb would intersect nothing.
+        let rect = rect.abs();
+        let aabb = Aabb::new(
+            point![rect.position.x / ppm.0, rect.position.y / ppm.0],
+            point![
+                (rect.position.x + rect.size.x) / ppm.0,
+                (rect.position.y + rect.size.y) / ppm.0
+            ],
+        );
+
+        let colliders = VariantArray::new();
+        self.with_query_filter(mask, exclude, |world, filter| {
+            world
+                .query_pipeline
+                .colliders_with_aabb_intersecting_aabb(&aabb, |handle| {
+                    let passes = world
+                        .collider_set
+                        .get(*handle)
+                        .is_some_and(|c| filter.test(&world.rigid_body_set, *handle, c));
+                    if passes {
+                        colliders.push(world.nodes.collider_node(*handle));
+                    }
+                    true
+                })
+        });
+        colliders.into_shared()
+    }
+
+    // first collider hit by `shape` moving by `motion` from `from`, or an empty dictionary when there is none.
+    #[method]
+    fn cast_shape(
+        &self,
+        shape: Ref<Shape2D>,
+        from: Transform2D,
+        motion: Vector2,
+        #[opt] mask: Option<u32>,
+        #[opt] exclude: Option<VariantArray>,
+    ) -> Dictionary {
+        use rapier2d::prelude::*;
+        let ppm = self.pixels_per_meter;
+        let info = Dictionary::new();
+        let Some(shape) = shared_shape(&shape, ppm) else {return info.into_shared()};
+        let shape_pos = shape_position(from, ppm);
+        let shape_vel = vector![motion.x / ppm.0, motion.y / ppm.0];
+
+        self.with_query_filter(mask, exclude, |world, filter| {
+            let Some((handle, toi)) = world.query_pipeline.cast_shape(
+                &world.rigid_body_set,
+                &world.collider_set,
+                &shape_pos,
+                &shape_vel,
+                &*shape,
+                1.,
+                true,
+                filter,
+            ) else {return};
+
+            // the first witness is given in world space, the second relative to the cast shape.
+            let point = toi.witness1;
+            let shape_point = shape_pos * toi.witness2 + shape_vel * toi.toi;
+            info.insert("collider", world.nodes.collider_node(handle));
+            info.insert(
+                "body",
+                world
+                    .collider_set
+                    .get(handle)
+                    .and_then(Collider::parent)
+                    .and_then(|b| world.nodes.body_node(b)),
+            );
+            info.insert(
+                "position",
+                Vector2 {
+                    x: point.x * ppm.0,
+                    y: point.y * ppm.0,
+                },
+            );
+            info.insert(
+                "shape_position",
+                Vector2 {
+                    x: shape_point.x * ppm.0,
+                    y: shape_point.y * ppm.0,
+                },
+            );
+            info.insert(
+                "normal",
+                Vector2 {
+                    x: toi.normal1.x,
+                    y: toi.normal1.y,
+                },
+            );
+            info.insert("fraction", toi.toi);
+        });
+        info.into_shared()
+    }
+
+    // colliders overlapping `shape` placed at `transform`.
+    #[method]
+    fn intersect_shape(
+        &self,
+        shape: Ref<Shape2D>,
+        transform: Transform2D,
+        #[opt] mask: Option<u32>,
+        #[opt] exclude: Option<VariantArray>,
+    ) -> VariantArray {
+        let ppm = self.pixels_per_meter;
+        let colliders = VariantArray::new();
+        let Some(shape) = shared_shape(&shape, ppm) else {return colliders.into_shared()};
+        let shape_pos = shape_position(transform, ppm);
+
+        self.with_query_filter(mask, exclude, |world, filter| {
+            world.query_pipeline.intersections_with_shape(
+                &world.rigid_body_set,
+                &world.collider_set,
+                &shape_pos,
+                &*shape,
+                filter,
+                |handle| {
+                    colliders.push(world.nodes.collider_node(handle));
+                    true
+                },
+            )
+        });
+        colliders.into_shared()
+    }
+
+    // closest points between two colliders of this world, or an empty dictionary if either is not.
+    // the distance turns negative while they penetrate.
+    #[method]
+    fn closest_points(&self, collider_a: Ref<Node2D>, collider_b: Ref<Node2D>) -> Dictionary {
+        use rapier2d::prelude::*;
+        let ppm = self.pixels_per_meter;
+        let info = Dictionary::new();
+        let world = self.rapier_world.borrow();
+
+        let collider = |node: Ref<Node2D>| {
+            // freed nodes count as colliders outside of this world.
+            let node = unsafe { node.assume_safe_if_sane() }?;
+            let handle = world.nodes.collider_handle(node)?;
+            world.collider_set.get(handle)
+        };
+        let Some(a) = collider(collider_a) else {return info.into_shared()};
+        let Some(b) = collider(collider_b) else {return info.into_shared()};
+        let Ok(Some(contact)) = rapier2d::parry::query::contact(
+            a.position(),
+            a.shape(),
+            b.position(),
+            b.shape(),
+            Real::MAX,
+        ) else {return info.into_shared()};
+
+        info.insert("distance", contact.dist * ppm.0);
+        info.insert(
+            "point_a",
+            Vector2 {
+                x: contact.point1.x * ppm.0,
+                y: contact.point1.y * ppm.0,
+            },
+        );
+        info.insert(
+            "point_b",
+            Vector2 {
+                x: contact.point2.x * ppm.0,
+                y: contact.point2.y * ppm.0,
+            },
+        );
+        info.insert(
+            "normal",
+            Vector2 {
+                x: contact.normal1.x,
+                y: contact.normal1.y,
+            },
+        );
+        info.into_shared()
     }
 
     #[method]
@@ -112,6 +469,28 @@ impl GodotRapier2DWorld {
             }};
         }
 
+        // joints, ropes and ragdolls insert into the world themselves, once what they attach to got registered.
+        // rays only need the world to cast into, and are attached to no body when directly under it.
+        macro_rules! register_joint {
+            ($i:ident) => {
+                register_joint!($i, ())
+            };
+            ($i:ident, $handle:expr) => {{
+                $i.map_mut(move |n, b| {
+                    n.add_to_world(b, &self.rapier_world, $handle, self.pixels_per_meter)
+                })
+                .unwrap();
+                n.connect(
+                    "tree_exiting",
+                    base,
+                    "_unregister_child",
+                    (VariantArray::from_iter(vec![node])).into_shared(),
+                    Object::CONNECT_ONESHOT,
+                )
+                .unwrap()
+            }};
+        }
+
         if let Some(i) = n.cast_instance::<GodotRapier2DFixedBody>() {
             register_child!(i)
         } else if let Some(i) = n.cast_instance::<GodotRapier2DDynamicBody>() {
@@ -120,6 +499,20 @@ impl GodotRapier2DWorld {
             register_child!(i)
         } else if let Some(i) = n.cast_instance::<GodotRapier2DKinematicVelocityBasedBody>() {
             register_child!(i)
+        } else if let Some(i) = n.cast_instance::<GodotRapier2DCharacterBody>() {
+            register_child!(i)
+        } else if let Some(i) = n.cast_instance::<GodotRapier2DFixedJoint>() {
+            register_joint!(i)
+        } else if let Some(i) = n.cast_instance::<GodotRapier2DRevoluteJoint>() {
+            register_joint!(i)
+        } else if let Some(i) = n.cast_instance::<GodotRapier2DPrismaticJoint>() {
+            register_joint!(i)
+        } else if let Some(i) = n.cast_instance::<GodotRapier2DRope>() {
+            register_joint!(i)
+        } else if let Some(i) = n.cast_instance::<GodotRapier2DRagdoll>() {
+            register_joint!(i)
+        } else if let Some(i) = n.cast_instance::<GodotRapier2DRayCast>() {
+            register_joint!(i, None)
         }
     }
 
@@ -141,6 +534,20 @@ impl GodotRapier2DWorld {
             unregister_child!(i)
         } else if let Some(i) = n.cast_instance::<GodotRapier2DKinematicVelocityBasedBody>() {
             unregister_child!(i)
+        } else if let Some(i) = n.cast_instance::<GodotRapier2DCharacterBody>() {
+            unregister_child!(i)
+        } else if let Some(i) = n.cast_instance::<GodotRapier2DFixedJoint>() {
+            unregister_child!(i)
+        } else if let Some(i) = n.cast_instance::<GodotRapier2DRevoluteJoint>() {
+            unregister_child!(i)
+        } else if let Some(i) = n.cast_instance::<GodotRapier2DPrismaticJoint>() {
+            unregister_child!(i)
+        } else if let Some(i) = n.cast_instance::<GodotRapier2DRope>() {
+            unregister_child!(i)
+        } else if let Some(i) = n.cast_instance::<GodotRapier2DRagdoll>() {
+            unregister_child!(i)
+        } else if let Some(i) = n.cast_instance::<GodotRapier2DRayCast>() {
+            unregister_child!(i)
         }
     }
 }
@@ -18,12 +18,49 @@ fn init(handle: InitHandle) {
     handle.add_tool_class_as::<classes::godot_rapier_2d_body::GodotRapier2DKinematicVelocityBasedBody>(
         "Rapier2DKinematicVelocityBasedBody".into(),
     );
+    handle.add_tool_class_as::<classes::godot_rapier_2d_body::GodotRapier2DCharacterBody>(
+        "Rapier2DCharacterBody".into(),
+    );
     handle.add_tool_class_as::<classes::godot_rapier_2d_collider::GodotRapier2DColliderBall>(
         "Rapier2DBallCollider".into(),
     );
     handle.add_tool_class_as::<classes::godot_rapier_2d_collider::GodotRapier2DColliderCuboid>(
         "Rapier2DCuboidCollider".into(),
     );
+    handle.add_tool_class_as::<classes::godot_rapier_2d_collider::GodotRapier2DColliderCapsule>(
+        "Rapier2DCapsuleCollider".into(),
+    );
+    handle.add_tool_class_as::<classes::godot_rapier_2d_collider::GodotRapier2DColliderSegment>(
+        "Rapier2DSegmentCollider".into(),
+    );
+    handle.add_tool_class_as::<classes::godot_rapier_2d_collider::GodotRapier2DColliderTriangle>(
+        "Rapier2DTriangleCollider".into(),
+    );
+    handle.add_tool_class_as::<classes::godot_rapier_2d_collider::GodotRapier2DColliderPolygon>(
+        "Rapier2DPolygonCollider".into(),
+    );
+    handle
+        .add_tool_class_as::<classes::godot_rapier_2d_collider::GodotRapier2DColliderHeightfield>(
+            "Rapier2DHeightfieldCollider".into(),
+        );
+    handle.add_tool_class_as::<classes::godot_rapier_2d_joint::GodotRapier2DFixedJoint>(
+        "Rapier2DFixedJoint".into(),
+    );
+    handle.add_tool_class_as::<classes::godot_rapier_2d_joint::GodotRapier2DRevoluteJoint>(
+        "Rapier2DRevoluteJoint".into(),
+    );
+    handle.add_tool_class_as::<classes::godot_rapier_2d_joint::GodotRapier2DPrismaticJoint>(
+        "Rapier2DPrismaticJoint".into(),
+    );
+    handle.add_tool_class_as::<classes::godot_rapier_2d_rope::GodotRapier2DRope>(
+        "Rapier2DRope".into(),
+    );
+    handle.add_tool_class_as::<classes::godot_rapier_2d_ragdoll::GodotRapier2DRagdoll>(
+        "Rapier2DRagdoll".into(),
+    );
+    handle.add_tool_class_as::<classes::godot_rapier_2d_ray_cast::GodotRapier2DRayCast>(
+        "Rapier2DRayCast".into(),
+    );
 }
 
 godot_init!(init);